@@ -0,0 +1,134 @@
+use std::{
+    panic,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use napi::{Env, JsFunction, Ref};
+
+/// A call sent to the main thread
+type Job = Box<dyn FnOnce(Env) + Send>;
+
+enum Message {
+    Call(Job),
+    Done,
+}
+
+type ErrorSlot = Arc<Mutex<Option<napi::Error>>>;
+type Dispatcher = Arc<Mutex<Option<mpsc::Sender<Message>>>>;
+
+/// Sends [`Message::Done`] when the work of a scope returns or panics
+struct DoneGuard(mpsc::Sender<Message>);
+
+impl Drop for DoneGuard {
+    fn drop(&mut self) {
+        let _ = self.0.send(Message::Done);
+    }
+}
+
+/// A JavaScript function called synchronously from Rust
+///
+/// The function is owned through a napi [`Ref`], which is released by [`JsCallbacks::release`].
+/// It can be called from any thread inside [`JsCallbacks::scope`], calls are sent to the main
+/// thread and run one at a time, so only the JavaScript calls are sequential.
+pub struct JsCallback {
+    func: Mutex<Option<Ref<()>>>,
+    error: ErrorSlot,
+    dispatcher: Dispatcher,
+}
+
+impl JsCallback {
+    pub fn call<R: Send + 'static>(
+        self: &Arc<Self>,
+        f: impl FnOnce(&Env, JsFunction) -> napi::Result<R> + Send + 'static,
+    ) -> napi::Result<R> {
+        let dispatcher = self.dispatcher.lock().unwrap().clone().ok_or_else(|| {
+            napi::Error::from_reason("JavaScript callbacks can only be called while generating")
+        })?;
+
+        let (tx, rx) = mpsc::channel();
+        let callback = self.clone();
+        let job: Job = Box::new(move |env| {
+            let res = match callback.func.lock().unwrap().as_ref() {
+                Some(func) => env.get_reference_value_unchecked::<JsFunction>(func),
+                None => Err(napi::Error::from_reason("The generator has been released")),
+            };
+            let _ = tx.send(res.and_then(|func| f(&env, func)));
+        });
+
+        dispatcher
+            .send(Message::Call(job))
+            .map_err(|_| napi::Error::from_reason("The generation has finished"))?;
+        rx.recv().map_err(|_| napi::Error::from_reason("The generation has finished"))?
+    }
+
+    /// Report an error to the running [`JsCallbacks::scope`], only the first error is kept
+    pub fn report(&self, error: napi::Error) {
+        self.error.lock().unwrap().get_or_insert(error);
+    }
+}
+
+impl Drop for JsCallback {
+    fn drop(&mut self) {
+        if let Some(func) = self.func.get_mut().unwrap().take() {
+            // Not released, i.e. the env is being torn down, the reference is freed with it
+            std::mem::forget(func);
+        }
+    }
+}
+
+/// The JavaScript callbacks of a generator
+#[derive(Default)]
+pub struct JsCallbacks {
+    callbacks: Vec<Arc<JsCallback>>,
+    error: ErrorSlot,
+    dispatcher: Dispatcher,
+}
+
+impl JsCallbacks {
+    pub fn create(&mut self, env: &Env, func: JsFunction) -> napi::Result<Arc<JsCallback>> {
+        let callback = Arc::new(JsCallback {
+            func: Mutex::new(Some(env.create_reference(func)?)),
+            error: self.error.clone(),
+            dispatcher: self.dispatcher.clone(),
+        });
+        self.callbacks.push(callback.clone());
+        Ok(callback)
+    }
+
+    /// Run `f` on another thread with callbacks enabled, the main thread runs the callbacks
+    /// meanwhile. Fails with the first error reported by a callback
+    pub fn scope<R: Send>(&self, env: &Env, f: impl FnOnce() -> R + Send) -> napi::Result<R> {
+        let (tx, rx) = mpsc::channel();
+        *self.dispatcher.lock().unwrap() = Some(tx.clone());
+
+        let res = thread::scope(|s| {
+            let worker = s.spawn(move || {
+                let _done = DoneGuard(tx);
+                f()
+            });
+            while let Ok(Message::Call(job)) = rx.recv() {
+                job(*env);
+            }
+            worker.join()
+        });
+
+        // calls after the scope fail instead of waiting for the main thread
+        self.dispatcher.lock().unwrap().take();
+        let res = res.unwrap_or_else(|e| panic::resume_unwind(e));
+        match self.error.lock().unwrap().take() {
+            Some(error) => Err(error),
+            None => Ok(res),
+        }
+    }
+
+    /// Release the references of all callbacks, they fail if called afterwards
+    pub fn release(&mut self, env: Env) -> napi::Result<()> {
+        for callback in self.callbacks.drain(..) {
+            if let Some(mut func) = callback.func.lock().unwrap().take() {
+                func.unref(env)?;
+            }
+        }
+        Ok(())
+    }
+}
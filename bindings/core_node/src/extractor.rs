@@ -0,0 +1,46 @@
+use std::{borrow::Cow, sync::Arc};
+
+use napi::{Env, JsFunction, ValueType};
+use rustc_hash::FxHashSet as HashSet;
+
+use crate::callback::{JsCallback, JsCallbacks};
+
+/// A custom extractor defined in JavaScript
+#[napi(object, object_to_js = false)]
+pub struct JsExtractor {
+    /// An extension like `tpl`, or a glob relative to the base directory like `views/**/*.html`
+    pub pattern: String,
+    /// Called with the file content, returns the candidates. Errors are thrown after the generation
+    #[napi(ts_type = "(content: string) => string[]")]
+    pub extract: JsFunction,
+}
+
+/// A JavaScript extract function, errors are thrown after the generation
+pub struct JsExtractFn {
+    func: Arc<JsCallback>,
+}
+
+impl JsExtractFn {
+    pub fn new(callbacks: &mut JsCallbacks, env: &Env, func: JsFunction) -> napi::Result<Self> {
+        Ok(Self { func: callbacks.create(env, func)? })
+    }
+
+    pub fn call<'a>(&self, input: &'a str) -> HashSet<Cow<'a, str>> {
+        let content = input.to_owned();
+        let candidates = self.func.call(move |env, func| {
+            let candidates = func.call(None, &[env.create_string(&content)?])?;
+            match candidates.get_type()? {
+                ValueType::Undefined | ValueType::Null => Ok(Vec::new()),
+                _ => env.from_js_value::<Vec<String>, _>(candidates),
+            }
+        });
+
+        match candidates {
+            Ok(candidates) => candidates.into_iter().map(Cow::Owned).collect(),
+            Err(e) => {
+                self.func.report(e);
+                HashSet::default()
+            }
+        }
+    }
+}
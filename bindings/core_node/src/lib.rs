@@ -1,22 +1,40 @@
 use std::sync::Arc;
 
+use napi::{bindgen_prelude::ObjectFinalize, Env};
 use rswind::{
-    config::{GeneratorConfig, DEFAULT_CONFIG_PATH},
+    config::{load::ConfigFile, GeneratorConfig},
     generator::{self, GeneratorInput},
     glob::GlobFilter,
-    preset::{tailwind_preset, tailwind_theme},
-    processor::{self, GenerateWith, ParGenerateWith},
+    preset::{builtin_plugins, tailwind_preset, tailwind_theme},
+    processor::{self, GenerateWith},
 };
 use rswind_extractor::{CollectExtracted, Extractable, Extractor};
 use serde::Deserialize;
 use serde_json::{from_value, Value};
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+use crate::{
+    callback::JsCallbacks,
+    extractor::{JsExtractFn, JsExtractor},
+    plugin::{JsPlugin, JsPluginAdapter},
+};
+
 #[macro_use]
 extern crate napi_derive;
 
-#[napi]
-pub struct Generator(generator::Generator);
+mod callback;
+mod extractor;
+mod plugin;
+
+/// A generator, the config file with the files it extends, and the JavaScript callbacks of plugins
+#[napi(custom_finalize)]
+pub struct Generator(generator::Generator, Vec<String>, JsCallbacks);
+
+impl ObjectFinalize for Generator {
+    fn finalize(mut self, env: Env) -> napi::Result<()> {
+        self.2.release(env)
+    }
+}
 
 #[napi(object)]
 pub struct GenerateResult {
@@ -48,36 +66,61 @@ impl From<processor::ResultKind> for ResultKind {
 #[napi]
 impl Generator {
     #[napi]
-    pub fn generate_with(&mut self, candidates: Vec<(String, String)>) -> GenerateResult {
-        candidates
-            .iter()
-            .map(GeneratorInput::from)
-            .glob_filter(&self.0.glob)
-            .collect_extracted()
-            .par_generate_with(&mut self.0.processor)
-            .into()
+    pub fn generate_with(
+        &mut self,
+        env: Env,
+        candidates: Vec<(String, String)>,
+    ) -> napi::Result<GenerateResult> {
+        let Self(app, _, callbacks) = self;
+        callbacks.scope(&env, || {
+            let candidates = candidates
+                .iter()
+                .map(GeneratorInput::from)
+                .glob_filter(&app.glob)
+                .map(|input| app.extractors.extractor(input).with_options(&app.extract))
+                .collect_extracted();
+
+            app.processor.run(candidates).into()
+        })
     }
 
     #[napi]
-    pub fn generate(&mut self) -> Arc<String> {
-        self.0.generate_contents().css
+    pub fn generate(&mut self, env: Env) -> napi::Result<Arc<String>> {
+        let Self(app, _, callbacks) = self;
+        callbacks.scope(&env, || app.generate_contents().css)
     }
 
     #[napi]
     pub fn generate_string(
         &mut self,
+        env: Env,
         input: String,
         #[napi(ts_arg_type = "'html' | 'ecma' | 'unknown'")] kind: Option<String>,
-    ) -> GenerateResult {
-        Extractor::new(&input, kind.as_deref().unwrap_or("unknown"))
-            .extract()
-            .par_generate_with(&mut self.0.processor)
-            .into()
+    ) -> napi::Result<GenerateResult> {
+        let Self(app, _, callbacks) = self;
+        callbacks.scope(&env, || {
+            let candidates = Extractor::new(&input, kind.as_deref().unwrap_or("unknown"))
+                .with_options(&app.extract)
+                .extract();
+
+            app.processor.run(candidates).into()
+        })
+    }
+
+    #[napi]
+    pub fn generate_candidate(
+        &mut self,
+        env: Env,
+        input: Vec<String>,
+    ) -> napi::Result<GenerateResult> {
+        let Self(app, _, callbacks) = self;
+        callbacks.scope(&env, || input.generate_with(&mut app.processor).into())
     }
 
+    /// The config file and the files it extends, empty if no config file is used
     #[napi]
-    pub fn generate_candidate(&mut self, input: Vec<String>) -> GenerateResult {
-        input.generate_with(&mut self.0.processor).into()
+    pub fn config_files(&self) -> Vec<String> {
+        self.1.clone()
     }
 }
 
@@ -89,18 +132,31 @@ fn init_tracing() {
         .init();
 }
 
-#[napi(object)]
+#[napi(object, object_to_js = false)]
 pub struct GeneratorOptions {
     pub base: Option<String>,
+    /// A config file path, a config object, or `false` to use the default config.
+    /// By default the config is searched in `base` and its parent directories
     #[napi(ts_type = "string | false | GeneratorConfig")]
     pub config: Option<Value>,
     pub watch: Option<bool>,
+    /// Extract and generate in parallel, calls to JavaScript handlers still run one at a time
     pub parallel: Option<bool>,
+    pub plugins: Option<Vec<JsPlugin>>,
+    /// Custom extractors, the first matching one is used for a file
+    pub extractors: Option<Vec<JsExtractor>>,
 }
 
 impl Default for GeneratorOptions {
     fn default() -> Self {
-        Self { base: None, config: None, watch: Some(true), parallel: Some(true) }
+        Self {
+            base: None,
+            config: None,
+            watch: Some(true),
+            parallel: Some(true),
+            plugins: None,
+            extractors: None,
+        }
     }
 }
 
@@ -112,23 +168,61 @@ pub enum RswindConfig {
 }
 
 #[napi]
-pub fn create_generator(options: Option<GeneratorOptions>) -> napi::Result<Generator> {
+pub fn create_generator(env: Env, options: Option<GeneratorOptions>) -> napi::Result<Generator> {
     let options = options.unwrap_or_default();
-    let config = match options.config {
-        Some(Value::String(path)) => GeneratorConfig::from_file(&path)?,
-        Some(obj @ Value::Object(_)) => from_value(obj)?,
-        Some(Value::Bool(false)) => GeneratorConfig::default(),
-        _ => GeneratorConfig::from_file(DEFAULT_CONFIG_PATH)?,
+    let config_file = match options.config {
+        Some(Value::String(ref path)) => Some(ConfigFile::load(path)?),
+        Some(Value::Object(_) | Value::Bool(false)) => None,
+        _ => ConfigFile::discover(options.base.as_deref().unwrap_or("."))?,
     };
+    let config = match (config_file.as_ref(), options.config) {
+        (Some(file), _) => file.to_config()?,
+        (None, Some(obj @ Value::Object(_))) => from_value(obj)?,
+        (None, _) => GeneratorConfig::default(),
+    };
+    let config_files = config_file.map_or_else(Vec::new, |file| {
+        file.sources.iter().map(|path| path.to_string_lossy().into_owned()).collect()
+    });
+
+    let plugins = options.plugins.unwrap_or_default();
+    let extractors = options.extractors.unwrap_or_default();
+
+    let builder = generator::Generator::builder()
+        .with_theme(tailwind_theme)
+        .with_preset(tailwind_preset)
+        .with_plugin_factories(builtin_plugins())
+        .with_config(config)
+        .with_watch(options.watch.unwrap_or(true))
+        .with_parallel(options.parallel.unwrap_or(true))
+        .with_base(options.base);
+
+    let mut callbacks = JsCallbacks::default();
+    let app = build_with_callbacks(&mut callbacks, &env, builder, plugins, extractors);
+    match app {
+        Ok(app) => Ok(Generator(app, config_files, callbacks)),
+        Err(e) => {
+            callbacks.release(env)?;
+            Err(e)
+        }
+    }
+}
+
+fn build_with_callbacks(
+    callbacks: &mut JsCallbacks,
+    env: &Env,
+    mut builder: generator::GeneratorBuilder,
+    plugins: Vec<JsPlugin>,
+    extractors: Vec<JsExtractor>,
+) -> napi::Result<generator::Generator> {
+    for plugin in plugins {
+        builder = builder.with_plugin(JsPluginAdapter::new(callbacks, env, plugin)?);
+    }
+
+    for extractor in extractors {
+        let extract = JsExtractFn::new(callbacks, env, extractor.extract)?;
+        builder = builder.with_extractor(extractor.pattern, move |input| extract.call(input));
+    }
 
-    Ok(Generator(
-        generator::Generator::builder()
-            .with_theme(tailwind_theme)
-            .with_preset(tailwind_preset)
-            .with_config(config)
-            .with_watch(options.watch.unwrap_or(true))
-            .with_parallel(options.parallel.unwrap_or(true))
-            .with_base(options.base)
-            .build()?,
-    ))
+    // plugins only register handlers while building, no JavaScript is called
+    Ok(builder.build()?)
 }
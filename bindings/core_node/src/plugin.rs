@@ -0,0 +1,246 @@
+use std::sync::{Arc, Mutex};
+
+use napi::{Env, JsFunction, JsUnknown, ValueType};
+use rswind::{
+    css::{Decl, DeclList, Rule},
+    design::DesignSystem,
+    parse::UtilityBuilder,
+    plugin::Plugin,
+    process::RawValueDef,
+    types::CssTypeValidator,
+};
+use rustc_hash::FxHashMap as HashMap;
+use serde_json::Value;
+use smol_str::SmolStr;
+use tracing::warn;
+
+use crate::callback::{JsCallback, JsCallbacks};
+
+/// A plugin defined in JavaScript
+#[napi(object, object_to_js = false)]
+pub struct JsPlugin {
+    pub name: String,
+    /// Static utilities, e.g. `{ 'content-auto': { contentVisibility: 'auto' } }`
+    #[napi(ts_type = "Record<string, CssObject>")]
+    pub add_utilities: Option<Value>,
+    /// Dynamic utilities with a JavaScript handler
+    pub match_utilities: Option<Vec<MatchUtility>>,
+    /// Static variants, e.g. `{ optional: '&:optional' }`
+    #[napi(ts_type = "Record<string, string | string[]>")]
+    pub add_variant: Option<Value>,
+}
+
+#[napi(object, object_to_js = false)]
+pub struct MatchUtility {
+    /// The key of the utility, e.g. `tab`
+    pub key: String,
+    /// Called with the resolved value and modifier, returns `null` if the value doesn't match.
+    /// Results are cached, errors are thrown after the generation
+    #[napi(ts_type = "(value: string, modifier: string | null) => CssObject | null | undefined")]
+    pub handler: JsFunction,
+    /// The theme key to read values from, e.g. `spacing`
+    pub theme: Option<String>,
+    /// The type validator for arbitrary values, e.g. `length`
+    #[napi(js_name = "type")]
+    pub validator: Option<String>,
+    #[napi(ts_type = "ValueDefinition")]
+    pub modifier: Option<Value>,
+    pub negative: Option<bool>,
+    pub fraction: Option<bool>,
+}
+
+/// A [`JsPlugin`] converted to a [`Plugin`]
+pub struct JsPluginAdapter {
+    name: String,
+    utilities: Vec<(SmolStr, Rule)>,
+    match_utilities: Mutex<Vec<UtilityBuilder>>,
+    variants: Vec<(SmolStr, Vec<SmolStr>)>,
+}
+
+impl JsPluginAdapter {
+    pub fn new(callbacks: &mut JsCallbacks, env: &Env, plugin: JsPlugin) -> napi::Result<Self> {
+        let utilities = match plugin.add_utilities {
+            Some(Value::Object(map)) => map
+                .iter()
+                .map(|(name, css)| {
+                    (SmolStr::from(name.trim_start_matches('.')), rule_from_css("&", css))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let variants = match plugin.add_variant {
+            Some(Value::Object(map)) => map
+                .into_iter()
+                .filter_map(|(name, selectors)| {
+                    let selectors = match selectors {
+                        Value::String(s) => vec![SmolStr::from(s)],
+                        Value::Array(list) => {
+                            list.into_iter().filter_map(|s| s.as_str().map(SmolStr::from)).collect()
+                        }
+                        _ => return None,
+                    };
+                    Some((SmolStr::from(name), selectors))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let match_utilities = plugin
+            .match_utilities
+            .unwrap_or_default()
+            .into_iter()
+            .map(|utility| build_utility(callbacks, env, utility))
+            .collect::<napi::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            name: plugin.name,
+            utilities,
+            match_utilities: Mutex::new(match_utilities),
+            variants,
+        })
+    }
+}
+
+impl Plugin for JsPluginAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn utilities(&self, design: &mut DesignSystem) {
+        for (key, rule) in self.utilities.iter() {
+            match rule.rules.is_empty() {
+                true => design.add_static(key.clone(), DeclList(rule.decls.clone())),
+                false => design.add_static_rule(key.clone(), rule.clone()),
+            };
+        }
+
+        for builder in self.match_utilities.lock().unwrap().drain(..) {
+            match builder.parse(&design.theme) {
+                Ok((key, utility)) => design.add_utility(&key, utility),
+                Err(e) => warn!("Failed to add utility from plugin `{}`: {}", self.name, e),
+            }
+        }
+    }
+
+    fn variants(&self, design: &mut DesignSystem) {
+        for (key, selectors) in self.variants.iter() {
+            design.add_variant(key.clone(), selectors.clone());
+        }
+    }
+}
+
+fn build_utility(
+    callbacks: &mut JsCallbacks,
+    env: &Env,
+    utility: MatchUtility,
+) -> napi::Result<UtilityBuilder> {
+    let handler =
+        JsHandler { func: callbacks.create(env, utility.handler)?, cache: Mutex::default() };
+
+    let mut builder = UtilityBuilder::new_optional(utility.key, move |meta, value| {
+        handler.call(value, meta.modifier.clone())
+    });
+
+    if let Some(theme) = utility.theme {
+        builder = builder.with_theme(theme.as_str());
+    }
+    if let Some(validator) = utility.validator {
+        builder = builder
+            .with_validator(serde_json::from_value::<CssTypeValidator>(Value::String(validator))?);
+    }
+    if let Some(modifier) = utility.modifier {
+        builder = builder.with_modifier(serde_json::from_value::<RawValueDef>(modifier)?);
+    }
+    if utility.negative.unwrap_or_default() {
+        builder = builder.support_negative();
+    }
+    if utility.fraction.unwrap_or_default() {
+        builder = builder.support_fraction();
+    }
+
+    Ok(builder)
+}
+
+type HandlerArgs = (SmolStr, Option<SmolStr>);
+
+/// A JavaScript utility handler, results are cached except errors
+struct JsHandler {
+    func: Arc<JsCallback>,
+    cache: Mutex<HashMap<HandlerArgs, Option<Rule>>>,
+}
+
+impl JsHandler {
+    fn call(&self, value: SmolStr, modifier: Option<SmolStr>) -> Option<Rule> {
+        let args = (value, modifier);
+        if let Some(rule) = self.cache.lock().unwrap().get(&args) {
+            return rule.clone();
+        }
+
+        let (value, modifier) = args.clone();
+        let css = self.func.call(move |env, func| {
+            let [value, modifier] = to_js_args(env, &value, modifier.as_deref())?;
+            let css = func.call(None, &[value, modifier])?;
+            match css.get_type()? {
+                ValueType::Undefined | ValueType::Null => Ok(None),
+                _ => env.from_js_value::<Value, _>(css).map(Some),
+            }
+        });
+
+        match css {
+            Ok(css) => {
+                let rule = css.map(|css| rule_from_css("&", &css));
+                self.cache.lock().unwrap().insert(args, rule.clone());
+                rule
+            }
+            // thrown by the handler, rethrown after the generation
+            Err(e) => {
+                self.func.report(e);
+                None
+            }
+        }
+    }
+}
+
+fn to_js_args(env: &Env, value: &str, modifier: Option<&str>) -> napi::Result<[JsUnknown; 2]> {
+    let modifier = match modifier {
+        Some(modifier) => env.create_string(modifier)?.into_unknown(),
+        None => env.get_null()?.into_unknown(),
+    };
+    Ok([env.create_string(value)?.into_unknown(), modifier])
+}
+
+/// Convert a css object like `{ backgroundColor: 'red', '&:hover': { color: 'blue' } }` to [`Rule`]
+fn rule_from_css(selector: &str, css: &Value) -> Rule {
+    let mut rule = Rule::new_with_decls(selector, vec![]);
+    let Value::Object(map) = css else { return rule };
+
+    for (key, value) in map {
+        match value {
+            Value::String(s) => rule.decls.push(Decl::new(property_name(key), s.as_str())),
+            Value::Number(n) => rule.decls.push(Decl::new(property_name(key), n.to_string())),
+            Value::Object(_) => rule.rules.0.push(rule_from_css(key, value)),
+            _ => {}
+        }
+    }
+
+    rule
+}
+
+/// `backgroundColor` -> `background-color`, custom properties are kept as is
+fn property_name(name: &str) -> SmolStr {
+    if name.starts_with("--") || !name.contains(|c: char| c.is_ascii_uppercase()) {
+        return name.into();
+    }
+
+    let mut property = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            property.push('-');
+            property.push(c.to_ascii_lowercase());
+        } else {
+            property.push(c);
+        }
+    }
+    property.into()
+}
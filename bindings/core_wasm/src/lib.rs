@@ -1,10 +1,10 @@
 use rswind::{
     generator::GeneratorBuilder,
-    preset::{tailwind_preset, tailwind_theme},
+    preset::{builtin_plugins, tailwind_preset, tailwind_theme},
     processor::GeneratorProcessor,
     GeneratorConfig,
 };
-use rswind_extractor::{Extractable, Extractor};
+use rswind_extractor::{ExtractOptions, Extractable, Extractor};
 use wasm_bindgen::prelude::*;
 
 extern crate console_error_panic_hook;
@@ -17,6 +17,7 @@ pub fn set_panic_hook() {
 #[wasm_bindgen]
 pub struct Generator {
     processor: GeneratorProcessor,
+    extract: ExtractOptions,
 }
 
 #[wasm_bindgen(js_name = createGenerator)]
@@ -28,20 +29,24 @@ pub fn create_generator(config: Option<GeneratorConfig>) -> Result<Generator, Js
 impl Generator {
     #[wasm_bindgen(constructor)]
     pub fn new(config: Option<GeneratorConfig>) -> Result<Generator, JsError> {
+        let config = config.unwrap_or_default();
         Ok(Generator {
+            extract: config.extract.to_options(),
             processor: GeneratorBuilder::new()
                 .with_parallel(false)
                 .with_theme(tailwind_theme)
                 .with_preset(tailwind_preset)
+                .with_plugin_factories(builtin_plugins())
                 .with_watch(true)
-                .with_config(config.unwrap_or_default())
+                .with_config(config)
                 .build_processor()?,
         })
     }
 
     #[wasm_bindgen]
     pub fn generate(&mut self, css: String, typ: String) -> String {
-        self.processor.run_with(Extractor::new(&css, &*typ).extract()).css.as_str().to_owned()
+        let candidates = Extractor::new(&css, &*typ).with_options(&self.extract).extract();
+        self.processor.run_with(candidates).css.as_str().to_owned()
     }
 
     #[wasm_bindgen(js_name = generateWith)]
@@ -2,7 +2,7 @@ use std::{iter, rc::Rc};
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use either::Either::{Left, Right};
-use rswind::extract::{Extractable, Extractor, InputKind};
+use rswind::extract::{html::FileType, Extractable, Extractor, InputKind};
 use rswind::generator::GeneratorBuilder;
 use rswind::preset::{tailwind_preset, tailwind_theme};
 use rswind::process::ValuePreprocessor;
@@ -52,7 +52,7 @@ pub fn bench_all(c: &mut Criterion) {
     c.bench_function("Generate all rules", |b| {
         b.iter(|| {
             let mut app = create_processor();
-            let input = Extractor::new(&fixture, InputKind::Html).extract();
+            let input = Extractor::new(&fixture, InputKind::Html(FileType::Html)).extract();
             let _a = app.run_with(input);
         });
     });
@@ -60,7 +60,7 @@ pub fn bench_all(c: &mut Criterion) {
     c.bench_function("Generate all rules parallel", |b| {
         b.iter(|| {
             let mut app = create_processor();
-            let input = Extractor::new(&fixture, InputKind::Html).extract();
+            let input = Extractor::new(&fixture, InputKind::Html(FileType::Html)).extract();
             let _a = app.run_parallel_with(input);
         });
     });
@@ -76,7 +76,7 @@ pub fn bench_static(c: &mut Criterion) {
     c.bench_function("parse basic", |b| {
         b.iter(|| {
             let mut app = create_processor();
-            let input = Extractor::new(r#"<div class="flex">"#, InputKind::Html);
+            let input = Extractor::new(r#"<div class="flex">"#, InputKind::Html(FileType::Html));
             let _a = app.run_with(input.extract());
         });
     });
@@ -88,7 +88,7 @@ pub fn bench_static(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("Normal", i), i, |b, _| {
             b.iter(|| {
                 let mut app = create_processor();
-                let input = Extractor::new(&input, InputKind::Html);
+                let input = Extractor::new(&input, InputKind::Html(FileType::Html));
                 let _a = app.run_with(input.extract());
             });
         });
@@ -96,18 +96,18 @@ pub fn bench_static(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("Parallel", i), i, |b, _| {
             b.iter(|| {
                 let mut app = create_processor();
-                let input = Extractor::new(&input, InputKind::Html);
+                let input = Extractor::new(&input, InputKind::Html(FileType::Html));
                 let _a = app.run_parallel_with(input.extract());
             });
         });
 
         group.bench_with_input(BenchmarkId::new("Without Extract", i), i, |b, _| {
-            let extracted = Extractor::new(&input, InputKind::Html);
+            let extracted = Extractor::new(&input, InputKind::Html(FileType::Html));
             let extracted = Rc::new(extracted.extract());
 
             b.iter(|| {
                 let mut app = create_processor();
-                let _a = app.run_with(Rc::clone(&extracted).iter().copied());
+                let _a = app.run_with(Rc::clone(&extracted).iter());
             });
         });
     }
@@ -1,4 +1,4 @@
-use rswind::extract::{Extractable, Extractor, InputKind};
+use rswind::extract::{html::FileType, Extractable, Extractor, InputKind};
 use rswind::generator::GeneratorBuilder;
 use rswind::preset::{tailwind_preset, tailwind_theme};
 
@@ -8,9 +8,11 @@ fn main() {
         .with_preset(tailwind_preset)
         .build_processor()
         .unwrap();
-    let input =
-        Extractor::new(r#"<div class="flex text-sm text-blue-500"></div>"#, InputKind::Html)
-            .extract();
+    let input = Extractor::new(
+        r#"<div class="flex text-sm text-blue-500"></div>"#,
+        InputKind::Html(FileType::Html),
+    )
+    .extract();
     let css = app.run_with(input);
     println!("{}", css.css);
 }
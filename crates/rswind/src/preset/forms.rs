@@ -0,0 +1,334 @@
+//! Form element resets, like `@tailwindcss/forms`
+//!
+//! With the `base` strategy, form elements are styled with element selectors,
+//! with the `class` strategy, styles are only applied through `form-*` utilities.
+//! Colors and spacing are read from the `colors` and `spacing` theme.
+
+use rswind_core::{
+    design::DesignSystem,
+    theme::{values::FontSize, ThemeValue},
+};
+use rswind_css::{Decl, Rule};
+use rustc_hash::FxHashMap as HashMap;
+use smol_str::{format_smolstr, SmolStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Base,
+    Class,
+}
+
+const TEXT_INPUTS: &[&str] = &[
+    "[type='text']",
+    "input:where(:not([type]))",
+    "[type='email']",
+    "[type='url']",
+    "[type='password']",
+    "[type='number']",
+    "[type='date']",
+    "[type='datetime-local']",
+    "[type='month']",
+    "[type='search']",
+    "[type='tel']",
+    "[type='time']",
+    "[type='week']",
+    "[multiple]",
+    "textarea",
+    "select",
+];
+
+/// A style applied to `base` selectors and `class` selectors
+struct FormStyle {
+    base: Vec<SmolStr>,
+    /// `(utility, selector suffix)`
+    class: Vec<(&'static str, &'static str)>,
+    decls: Vec<Decl>,
+}
+
+impl FormStyle {
+    fn new<B: Into<SmolStr>>(
+        base: impl IntoIterator<Item = B>,
+        class: impl IntoIterator<Item = (&'static str, &'static str)>,
+        decls: impl IntoIterator<Item = (&'static str, SmolStr)>,
+    ) -> Self {
+        Self {
+            base: base.into_iter().map(Into::into).collect(),
+            class: class.into_iter().collect(),
+            decls: decls.into_iter().map(|(k, v)| Decl::new(k, v)).collect(),
+        }
+    }
+}
+
+struct ThemeValues<'a>(&'a DesignSystem);
+
+impl ThemeValues<'_> {
+    fn get(&self, key: &str, inner: &str, fallback: &str) -> SmolStr {
+        self.0.theme.get_value(key, inner).unwrap_or_else(|| fallback.into())
+    }
+
+    fn color(&self, color: &str, fallback: &str) -> SmolStr {
+        self.get("colors", color, fallback)
+    }
+
+    fn spacing(&self, spacing: &str, fallback: &str) -> SmolStr {
+        self.get("spacing", spacing, fallback)
+    }
+
+    /// `(font-size, line-height)` of `fontSize.base`
+    fn font_size(&self) -> (SmolStr, SmolStr) {
+        let font_size = self.0.get_theme("fontSize");
+        match font_size.as_deref().and_then(|t| t.get_value("base")) {
+            Some(ThemeValue::FontSize(FontSize::Plain(size))) => (size.clone(), "1.5rem".into()),
+            Some(ThemeValue::FontSize(FontSize::WithLineHeight((size, line_height)))) => {
+                (size.clone(), line_height.clone())
+            }
+            Some(ThemeValue::FontSize(FontSize::WithConfig((size, config)))) => {
+                (size.clone(), config.line_height.clone().unwrap_or_else(|| "1.5rem".into()))
+            }
+            _ => ("1rem".into(), "1.5rem".into()),
+        }
+    }
+}
+
+/// Encode `#` for colors used in svg data urls
+fn svg_color(color: &str) -> String {
+    color.replace('#', "%23")
+}
+
+fn styles(theme: &ThemeValues) -> Vec<FormStyle> {
+    let gray = theme.color("gray-500", "#6b7280");
+    let blue = theme.color("blue-600", "#2563eb");
+    let border_width = theme.get("borderWidth", "DEFAULT", "1px");
+    let rounded = theme.get("borderRadius", "none", "0px");
+    let (font_size, line_height) = theme.font_size();
+    let (sp2, sp3, sp4, sp10) = (
+        theme.spacing("2", "0.5rem"),
+        theme.spacing("3", "0.75rem"),
+        theme.spacing("4", "1rem"),
+        theme.spacing("10", "2.5rem"),
+    );
+
+    let focus = |offset: &'static str| {
+        [
+            ("outline", SmolStr::from("2px solid transparent")),
+            ("outline-offset", "2px".into()),
+            ("--tw-ring-inset", "var(--tw-empty,/*!*/ /*!*/)".into()),
+            ("--tw-ring-offset-width", offset.into()),
+            ("--tw-ring-offset-color", "#fff".into()),
+            ("--tw-ring-color", blue.clone()),
+            (
+                "--tw-ring-offset-shadow",
+                "var(--tw-ring-inset) 0 0 0 var(--tw-ring-offset-width) var(--tw-ring-offset-color)"
+                    .into(),
+            ),
+            (
+                "--tw-ring-shadow",
+                "var(--tw-ring-inset) 0 0 0 calc(1px + var(--tw-ring-offset-width)) var(--tw-ring-color)"
+                    .into(),
+            ),
+            ("box-shadow", "var(--tw-ring-offset-shadow), var(--tw-ring-shadow), var(--tw-shadow)".into()),
+        ]
+    };
+    let checked = [
+        ("border-color", SmolStr::from("transparent")),
+        ("background-color", "currentColor".into()),
+        ("background-size", "100% 100%".into()),
+        ("background-position", "center".into()),
+        ("background-repeat", "no-repeat".into()),
+    ];
+
+    let chevron = format_smolstr!(
+        r#"url("data:image/svg+xml,%3csvg xmlns='http://www.w3.org/2000/svg' fill='none' viewBox='0 0 20 20'%3e%3cpath stroke='{}' stroke-linecap='round' stroke-linejoin='round' stroke-width='1.5' d='M6 8l4 4 4-4'/%3e%3c/svg%3e")"#,
+        svg_color(&gray)
+    );
+
+    let text_focus = TEXT_INPUTS.iter().map(|s| format_smolstr!("{s}:focus"));
+
+    vec![
+        FormStyle::new(
+            TEXT_INPUTS.iter().copied(),
+            [
+                ("form-input", ""),
+                ("form-textarea", ""),
+                ("form-select", ""),
+                ("form-multiselect", ""),
+            ],
+            [
+                ("appearance", "none".into()),
+                ("background-color", "#fff".into()),
+                ("border-color", gray.clone()),
+                ("border-width", border_width.clone()),
+                ("border-radius", rounded.clone()),
+                ("padding-top", sp2.clone()),
+                ("padding-right", sp3.clone()),
+                ("padding-bottom", sp2.clone()),
+                ("padding-left", sp3.clone()),
+                ("font-size", font_size),
+                ("line-height", line_height),
+                ("--tw-shadow", "0 0 #0000".into()),
+            ],
+        ),
+        FormStyle::new(
+            text_focus,
+            [
+                ("form-input", ":focus"),
+                ("form-textarea", ":focus"),
+                ("form-select", ":focus"),
+                ("form-multiselect", ":focus"),
+            ],
+            focus("0px").into_iter().chain([("border-color", blue.clone())]),
+        ),
+        FormStyle::new(
+            ["input::placeholder", "textarea::placeholder"],
+            [("form-input", "::placeholder"), ("form-textarea", "::placeholder")],
+            [("color", gray.clone()), ("opacity", "1".into())],
+        ),
+        FormStyle::new(
+            ["::-webkit-datetime-edit-fields-wrapper"],
+            [("form-input", "::-webkit-datetime-edit-fields-wrapper")],
+            [("padding", "0".into())],
+        ),
+        FormStyle::new(
+            ["::-webkit-date-and-time-value"],
+            [("form-input", "::-webkit-date-and-time-value")],
+            [("min-height", "1.5em".into()), ("text-align", "inherit".into())],
+        ),
+        FormStyle::new(
+            ["::-webkit-datetime-edit"],
+            [("form-input", "::-webkit-datetime-edit")],
+            [("display", "inline-flex".into())],
+        ),
+        FormStyle::new(
+            ["select"],
+            [("form-select", "")],
+            [
+                ("background-image", chevron),
+                ("background-position", format_smolstr!("right {sp2} center")),
+                ("background-repeat", "no-repeat".into()),
+                ("background-size", "1.5em 1.5em".into()),
+                ("padding-right", sp10),
+                ("print-color-adjust", "exact".into()),
+            ],
+        ),
+        FormStyle::new(
+            ["[multiple]", "[size]:where(select:not([size=\"1\"]))"],
+            [],
+            [
+                ("background-image", "initial".into()),
+                ("background-position", "initial".into()),
+                ("background-repeat", "unset".into()),
+                ("background-size", "initial".into()),
+                ("padding-right", sp3),
+                ("print-color-adjust", "unset".into()),
+            ],
+        ),
+        FormStyle::new(
+            ["[type='checkbox']", "[type='radio']"],
+            [("form-checkbox", ""), ("form-radio", "")],
+            [
+                ("appearance", "none".into()),
+                ("padding", "0".into()),
+                ("print-color-adjust", "exact".into()),
+                ("display", "inline-block".into()),
+                ("vertical-align", "middle".into()),
+                ("background-origin", "border-box".into()),
+                ("user-select", "none".into()),
+                ("flex-shrink", "0".into()),
+                ("height", sp4.clone()),
+                ("width", sp4),
+                ("color", blue.clone()),
+                ("background-color", "#fff".into()),
+                ("border-color", gray),
+                ("border-width", border_width),
+                ("--tw-shadow", "0 0 #0000".into()),
+            ],
+        ),
+        FormStyle::new(["[type='checkbox']"], [("form-checkbox", "")], [("border-radius", rounded)]),
+        FormStyle::new(["[type='radio']"], [("form-radio", "")], [("border-radius", "100%".into())]),
+        FormStyle::new(
+            ["[type='checkbox']:focus", "[type='radio']:focus"],
+            [("form-checkbox", ":focus"), ("form-radio", ":focus")],
+            focus("2px"),
+        ),
+        FormStyle::new(
+            ["[type='checkbox']:checked", "[type='radio']:checked"],
+            [("form-checkbox", ":checked"), ("form-radio", ":checked")],
+            checked.clone(),
+        ),
+        FormStyle::new(
+            ["[type='checkbox']:checked"],
+            [("form-checkbox", ":checked")],
+            [(
+                "background-image",
+                r#"url("data:image/svg+xml,%3csvg viewBox='0 0 16 16' fill='white' xmlns='http://www.w3.org/2000/svg'%3e%3cpath d='M12.207 4.793a1 1 0 010 1.414l-5 5a1 1 0 01-1.414 0l-2-2a1 1 0 011.414-1.414L6.5 9.086l4.293-4.293a1 1 0 011.414 0z'/%3e%3c/svg%3e")"#.into(),
+            )],
+        ),
+        FormStyle::new(
+            ["[type='radio']:checked"],
+            [("form-radio", ":checked")],
+            [(
+                "background-image",
+                r#"url("data:image/svg+xml,%3csvg viewBox='0 0 16 16' fill='white' xmlns='http://www.w3.org/2000/svg'%3e%3ccircle cx='8' cy='8' r='3'/%3e%3c/svg%3e")"#.into(),
+            )],
+        ),
+        FormStyle::new(
+            ["[type='checkbox']:indeterminate"],
+            [("form-checkbox", ":indeterminate")],
+            checked.into_iter().chain([(
+                "background-image",
+                r#"url("data:image/svg+xml,%3csvg xmlns='http://www.w3.org/2000/svg' fill='none' viewBox='0 0 16 16'%3e%3cpath stroke='white' stroke-linecap='round' stroke-linejoin='round' stroke-width='2' d='M4 8h8'/%3e%3c/svg%3e")"#.into(),
+            )]),
+        ),
+        FormStyle::new(
+            ["[type='file']"],
+            [],
+            [
+                ("background", "unset".into()),
+                ("border-color", "inherit".into()),
+                ("border-width", "0".into()),
+                ("border-radius", "0".into()),
+                ("padding", "0".into()),
+                ("font-size", "unset".into()),
+                ("line-height", "inherit".into()),
+            ],
+        ),
+        FormStyle::new(
+            ["[type='file']:focus"],
+            [],
+            [("outline", "1px solid ButtonText".into()), ("outline", "1px auto -webkit-focus-ring-color".into())],
+        ),
+    ]
+}
+
+pub fn load_forms(design: &mut DesignSystem, strategy: Option<Strategy>) {
+    let styles = styles(&ThemeValues(design));
+
+    if strategy.map_or(true, |s| s == Strategy::Base) {
+        for style in styles.iter() {
+            design.add_base(Rule::new_with_decls(
+                style.base.iter().map(SmolStr::as_str).collect::<Vec<_>>().join(", "),
+                style.decls.clone(),
+            ));
+        }
+    }
+
+    if strategy.map_or(true, |s| s == Strategy::Class) {
+        let mut utilities: HashMap<&str, Rule> = HashMap::default();
+        for style in styles.iter() {
+            for (utility, suffix) in style.class.iter() {
+                let rule = utilities.entry(utility).or_insert_with(|| Rule::new([]));
+                match *suffix {
+                    "" => rule.decls.extend(style.decls.iter().cloned()),
+                    suffix => rule.rules.0.push(Rule::new_with_decls(
+                        format_smolstr!("&{suffix}"),
+                        style.decls.clone(),
+                    )),
+                }
+            }
+        }
+
+        for (key, rule) in utilities {
+            design.add_static_rule(key, rule);
+        }
+    }
+}
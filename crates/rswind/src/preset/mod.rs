@@ -2,7 +2,14 @@ use tracing::{enabled, info, Level};
 
 use rswind_core::design::DesignSystem;
 
+pub use plugins::builtin_plugins;
+
+#[cfg(feature = "forms")]
+pub mod forms;
+pub mod plugins;
 pub mod theme;
+#[cfg(feature = "typography")]
+pub mod typography;
 pub mod utility;
 pub mod variant;
 
@@ -37,3 +44,19 @@ pub fn tailwind_preset(design: &mut DesignSystem) {
         );
     }
 }
+
+/// Load `prose` utilities, see [`typography`] for details
+#[cfg(feature = "typography")]
+pub fn typography_preset(design: &mut DesignSystem) {
+    typography::load_typography(design);
+
+    info!("Loaded typography preset");
+}
+
+/// Load form element resets with both `base` and `class` strategy, see [`forms`] for details
+#[cfg(feature = "forms")]
+pub fn forms_preset(design: &mut DesignSystem) {
+    forms::load_forms(design, None);
+
+    info!("Loaded forms preset");
+}
@@ -0,0 +1,80 @@
+//! Built-in presets as plugins, so they can be enabled by name in config `plugins`
+
+use rswind_core::{
+    design::DesignSystem,
+    plugin::{Plugin, PluginError, PluginFactory},
+};
+use serde_json::Value;
+
+#[cfg(feature = "forms")]
+use super::forms::{self, Strategy};
+
+/// All built-in plugins, register them with
+/// [`GeneratorBuilder::with_plugin_factories`](rswind_core::generator::GeneratorBuilder::with_plugin_factories)
+#[allow(unused_mut, clippy::vec_init_then_push)]
+pub fn builtin_plugins() -> Vec<(&'static str, PluginFactory)> {
+    let mut plugins: Vec<(&'static str, PluginFactory)> = Vec::new();
+
+    #[cfg(feature = "typography")]
+    plugins.push(("typography", |_| Ok(Box::new(Typography))));
+    #[cfg(feature = "forms")]
+    plugins.push(("forms", |options| Forms::from_options(options).map(|f| Box::new(f) as _)));
+
+    plugins
+}
+
+/// `prose` utilities
+#[cfg(feature = "typography")]
+pub struct Typography;
+
+#[cfg(feature = "typography")]
+impl Plugin for Typography {
+    fn name(&self) -> &str {
+        "typography"
+    }
+
+    fn utilities(&self, design: &mut DesignSystem) {
+        super::typography_preset(design);
+    }
+}
+
+/// Form element resets, `strategy` can be `base` or `class`, both are used if not set
+#[cfg(feature = "forms")]
+#[derive(Debug, Default)]
+pub struct Forms {
+    pub strategy: Option<Strategy>,
+}
+
+#[cfg(feature = "forms")]
+impl Forms {
+    pub fn from_options(options: &Value) -> Result<Self, PluginError> {
+        let invalid = |message: &str| PluginError::InvalidOptions {
+            plugin: "forms".into(),
+            message: message.into(),
+        };
+
+        let strategy = match options {
+            Value::Null => None,
+            Value::Object(map) => match map.get("strategy") {
+                None | Some(Value::Null) => None,
+                Some(Value::String(s)) if s == "base" => Some(Strategy::Base),
+                Some(Value::String(s)) if s == "class" => Some(Strategy::Class),
+                Some(_) => return Err(invalid("`strategy` must be `base` or `class`")),
+            },
+            _ => return Err(invalid("options must be an object")),
+        };
+
+        Ok(Self { strategy })
+    }
+}
+
+#[cfg(feature = "forms")]
+impl Plugin for Forms {
+    fn name(&self) -> &str {
+        "forms"
+    }
+
+    fn utilities(&self, design: &mut DesignSystem) {
+        forms::load_forms(design, self.strategy);
+    }
+}
@@ -0,0 +1,425 @@
+//! `prose` utilities for styling long-form content, like `@tailwindcss/typography`
+//!
+//! Colors are read from the `typography` theme key, which can be customized with
+//! `theme.extend.typography`, e.g. `{ "links": "#0ea5e9", "invert-links": "#38bdf8" }`.
+
+use std::sync::Arc;
+
+use phf::phf_map;
+use rswind_core::{
+    design::{utilities::StaticUtility, DesignSystem},
+    ordering::OrderingKey,
+    process::{Utility, ValueDef},
+    theme::ThemeMap,
+};
+use rswind_css::{rule::RuleList, Decl, Rule};
+use rustc_hash::FxHashMap as HashMap;
+use smol_str::{format_smolstr, SmolStr};
+
+/// Elements inside `not-prose` are left unstyled
+const NOT_PROSE: &str = r#":not(:where([class~="not-prose"],[class~="not-prose"] *))"#;
+
+/// Values accepted by `prose-*`, `DEFAULT` is used by plain `prose`
+static PROSE_VALUES: phf::Map<&str, &str> = phf_map! {
+    "DEFAULT" => "DEFAULT",
+    "sm" => "sm",
+    "base" => "base",
+    "lg" => "lg",
+    "xl" => "xl",
+    "2xl" => "2xl",
+    "slate" => "slate",
+    "gray" => "gray",
+    "zinc" => "zinc",
+    "neutral" => "neutral",
+    "stone" => "stone",
+};
+
+/// Color keys with the light and inverted shade of a gray scale
+const COLOR_SHADES: &[(&str, &str, &str)] = &[
+    ("body", "700", "300"),
+    ("headings", "900", "white"),
+    ("lead", "600", "400"),
+    ("links", "900", "white"),
+    ("bold", "900", "white"),
+    ("counters", "500", "400"),
+    ("bullets", "300", "600"),
+    ("hr", "200", "700"),
+    ("quotes", "900", "100"),
+    ("quote-borders", "200", "700"),
+    ("captions", "500", "400"),
+    ("kbd", "900", "white"),
+    ("code", "900", "white"),
+    ("pre-code", "200", "300"),
+    ("pre-bg", "800", "rgb(0 0 0 / 50%)"),
+    ("th-borders", "300", "600"),
+    ("td-borders", "200", "700"),
+];
+
+type Styles = &'static [(&'static str, &'static [(&'static str, &'static str)])];
+
+const BASE_STYLES: Styles = &[
+    ("p", &[("margin-top", "1.25em"), ("margin-bottom", "1.25em")]),
+    (
+        "[class~=\"lead\"]",
+        &[
+            ("color", "var(--tw-prose-lead)"),
+            ("font-size", "1.25em"),
+            ("line-height", "1.6"),
+            ("margin-top", "1.2em"),
+            ("margin-bottom", "1.2em"),
+        ],
+    ),
+    (
+        "a",
+        &[
+            ("color", "var(--tw-prose-links)"),
+            ("text-decoration", "underline"),
+            ("font-weight", "500"),
+        ],
+    ),
+    ("strong", &[("color", "var(--tw-prose-bold)"), ("font-weight", "600")]),
+    ("a strong, blockquote strong, thead th strong", &[("color", "inherit")]),
+    (
+        "ol",
+        &[
+            ("list-style-type", "decimal"),
+            ("margin-top", "1.25em"),
+            ("margin-bottom", "1.25em"),
+            ("padding-inline-start", "1.625em"),
+        ],
+    ),
+    (
+        "ul",
+        &[
+            ("list-style-type", "disc"),
+            ("margin-top", "1.25em"),
+            ("margin-bottom", "1.25em"),
+            ("padding-inline-start", "1.625em"),
+        ],
+    ),
+    ("li", &[("margin-top", "0.5em"), ("margin-bottom", "0.5em")]),
+    ("ol > li::marker", &[("font-weight", "400"), ("color", "var(--tw-prose-counters)")]),
+    ("ul > li::marker", &[("color", "var(--tw-prose-bullets)")]),
+    (
+        "hr",
+        &[
+            ("border-color", "var(--tw-prose-hr)"),
+            ("border-top-width", "1px"),
+            ("margin-top", "3em"),
+            ("margin-bottom", "3em"),
+        ],
+    ),
+    (
+        "blockquote",
+        &[
+            ("font-weight", "500"),
+            ("font-style", "italic"),
+            ("color", "var(--tw-prose-quotes)"),
+            ("border-inline-start-width", "0.25rem"),
+            ("border-inline-start-color", "var(--tw-prose-quote-borders)"),
+            ("quotes", "\"\\201C\"\"\\201D\"\"\\2018\"\"\\2019\""),
+            ("margin-top", "1.6em"),
+            ("margin-bottom", "1.6em"),
+            ("padding-inline-start", "1em"),
+        ],
+    ),
+    (
+        "h1",
+        &[
+            ("color", "var(--tw-prose-headings)"),
+            ("font-weight", "800"),
+            ("font-size", "2.25em"),
+            ("margin-top", "0"),
+            ("margin-bottom", "0.8888889em"),
+            ("line-height", "1.1111111"),
+        ],
+    ),
+    (
+        "h2",
+        &[
+            ("color", "var(--tw-prose-headings)"),
+            ("font-weight", "700"),
+            ("font-size", "1.5em"),
+            ("margin-top", "2em"),
+            ("margin-bottom", "1em"),
+            ("line-height", "1.3333333"),
+        ],
+    ),
+    (
+        "h3",
+        &[
+            ("color", "var(--tw-prose-headings)"),
+            ("font-weight", "600"),
+            ("font-size", "1.25em"),
+            ("margin-top", "1.6em"),
+            ("margin-bottom", "0.6em"),
+            ("line-height", "1.6"),
+        ],
+    ),
+    (
+        "h4",
+        &[
+            ("color", "var(--tw-prose-headings)"),
+            ("font-weight", "600"),
+            ("margin-top", "1.5em"),
+            ("margin-bottom", "0.5em"),
+            ("line-height", "1.5"),
+        ],
+    ),
+    ("img, video, figure", &[("margin-top", "2em"), ("margin-bottom", "2em")]),
+    ("figure > *", &[("margin-top", "0"), ("margin-bottom", "0")]),
+    (
+        "figcaption",
+        &[
+            ("color", "var(--tw-prose-captions)"),
+            ("font-size", "0.875em"),
+            ("line-height", "1.4285714"),
+            ("margin-top", "0.8571429em"),
+        ],
+    ),
+    (
+        "kbd",
+        &[
+            ("font-weight", "500"),
+            ("font-family", "inherit"),
+            ("color", "var(--tw-prose-kbd)"),
+            ("font-size", "0.875em"),
+            ("border-radius", "0.3125rem"),
+            ("padding-inline", "0.375em"),
+            ("padding-block", "0.1875em"),
+        ],
+    ),
+    (
+        "code",
+        &[("color", "var(--tw-prose-code)"), ("font-weight", "600"), ("font-size", "0.875em")],
+    ),
+    ("code::before", &[("content", "\"`\"")]),
+    ("code::after", &[("content", "\"`\"")]),
+    (
+        "pre",
+        &[
+            ("color", "var(--tw-prose-pre-code)"),
+            ("background-color", "var(--tw-prose-pre-bg)"),
+            ("overflow-x", "auto"),
+            ("font-weight", "400"),
+            ("font-size", "0.875em"),
+            ("line-height", "1.7142857"),
+            ("margin-top", "1.7142857em"),
+            ("margin-bottom", "1.7142857em"),
+            ("border-radius", "0.375rem"),
+            ("padding-inline", "1.1428571em"),
+            ("padding-block", "0.8571429em"),
+        ],
+    ),
+    (
+        "pre code",
+        &[
+            ("background-color", "transparent"),
+            ("border-width", "0"),
+            ("border-radius", "0"),
+            ("padding", "0"),
+            ("font-weight", "inherit"),
+            ("color", "inherit"),
+            ("font-size", "inherit"),
+            ("font-family", "inherit"),
+            ("line-height", "inherit"),
+        ],
+    ),
+    ("pre code::before", &[("content", "none")]),
+    ("pre code::after", &[("content", "none")]),
+    (
+        "table",
+        &[
+            ("width", "100%"),
+            ("table-layout", "auto"),
+            ("margin-top", "2em"),
+            ("margin-bottom", "2em"),
+            ("font-size", "0.875em"),
+            ("line-height", "1.7142857"),
+        ],
+    ),
+    (
+        "thead",
+        &[("border-bottom-width", "1px"), ("border-bottom-color", "var(--tw-prose-th-borders)")],
+    ),
+    (
+        "thead th",
+        &[
+            ("color", "var(--tw-prose-headings)"),
+            ("font-weight", "600"),
+            ("vertical-align", "bottom"),
+            ("padding-inline", "0.5714286em"),
+            ("padding-bottom", "0.5714286em"),
+        ],
+    ),
+    (
+        "tbody tr",
+        &[("border-bottom-width", "1px"), ("border-bottom-color", "var(--tw-prose-td-borders)")],
+    ),
+    ("tbody td", &[("vertical-align", "baseline"), ("padding", "0.5714286em")]),
+];
+
+/// `(font-size, line-height, paragraph margin, h1, h2, h3)` of each size modifier
+const SIZES: &[(&str, [&str; 6])] = &[
+    ("sm", ["0.875rem", "1.7142857", "1.1428571em", "2.1428571em", "1.4285714em", "1.2857143em"]),
+    ("base", ["1rem", "1.75", "1.25em", "2.25em", "1.5em", "1.25em"]),
+    ("lg", ["1.125rem", "1.7777778", "1.3333333em", "2.6666667em", "1.6666667em", "1.3333333em"]),
+    ("xl", ["1.25rem", "1.8", "1.2em", "2.8em", "1.8em", "1.5em"]),
+    ("2xl", ["1.5rem", "1.6666667", "1.3333333em", "2.6666667em", "2em", "1.5em"]),
+];
+
+/// Element modifiers, e.g. `prose-headings:underline`
+const ELEMENT_VARIANTS: &[(&str, &str)] = &[
+    ("headings", "h1, h2, h3, h4, h5, h6, th"),
+    ("lead", "[class~=\"lead\"]"),
+    ("h1", "h1"),
+    ("h2", "h2"),
+    ("h3", "h3"),
+    ("h4", "h4"),
+    ("h5", "h5"),
+    ("h6", "h6"),
+    ("p", "p"),
+    ("a", "a"),
+    ("blockquote", "blockquote"),
+    ("figure", "figure"),
+    ("figcaption", "figcaption"),
+    ("strong", "strong"),
+    ("em", "em"),
+    ("kbd", "kbd"),
+    ("code", "code"),
+    ("pre", "pre"),
+    ("ol", "ol"),
+    ("ul", "ul"),
+    ("li", "li"),
+    ("table", "table"),
+    ("thead", "thead"),
+    ("tr", "tr"),
+    ("th", "th"),
+    ("td", "td"),
+    ("img", "img"),
+    ("video", "video"),
+    ("hr", "hr"),
+];
+
+pub fn load_typography(design: &mut DesignSystem) {
+    load_theme(design);
+
+    let typography = design.get_theme("typography").unwrap_or_default();
+    let colors = design.get_theme("colors").unwrap_or_default();
+
+    let mut prose = Utility::new(move |_, value| match value.as_str() {
+        "DEFAULT" => default_rule(&typography),
+        size @ ("sm" | "base" | "lg" | "xl" | "2xl") => size_rule(size),
+        gray => color_rule(gray, &colors),
+    });
+    prose.value_def = ValueDef::new(ThemeMap::Static(&PROSE_VALUES));
+    prose.ordering_key = Some(OrderingKey::Component);
+    design.add_utility("prose", prose);
+
+    let invert = COLOR_SHADES
+        .iter()
+        .map(|(key, _, _)| {
+            Decl::new(
+                format_smolstr!("--tw-prose-{key}"),
+                format_smolstr!("var(--tw-prose-invert-{key})"),
+            )
+        })
+        .collect();
+    design.add_static(
+        "prose-invert",
+        StaticUtility { selector: None, decls: invert, ordering: OrderingKey::ComponentModifier },
+    );
+
+    for (name, elements) in ELEMENT_VARIANTS {
+        design.add_variant(
+            format_smolstr!("prose-{name}"),
+            [format_smolstr!("& :is(:where({elements}){NOT_PROSE})")],
+        );
+    }
+}
+
+/// Fill the `typography` theme with gray colors, values from user config take precedence
+fn load_theme(design: &mut DesignSystem) {
+    let colors = design.get_theme("colors").unwrap_or_default();
+
+    let mut defaults = HashMap::default();
+    for (key, light, invert) in COLOR_SHADES {
+        defaults.insert(SmolStr::from(*key), shade(&colors, "gray", light));
+        defaults.insert(format_smolstr!("invert-{key}"), shade(&colors, "gray", invert));
+    }
+
+    let mut theme = ThemeMap::Dynamic(defaults);
+    if let Some(user) = design.theme.get("typography") {
+        theme.extend(user.iter().map(|(k, v)| (SmolStr::from(k), v)));
+    }
+    design.theme.insert("typography".into(), Arc::new(theme));
+}
+
+fn shade(colors: &ThemeMap, gray: &str, shade: &str) -> SmolStr {
+    match shade {
+        "white" => "#fff".into(),
+        s if s.starts_with(|c: char| c.is_ascii_digit()) => {
+            colors.get(&format_smolstr!("{gray}-{shade}")).unwrap_or_default()
+        }
+        s => s.into(),
+    }
+}
+
+fn element_rule(element: &str, decls: impl IntoIterator<Item = Decl>) -> Rule {
+    let selector = match element.split_once("::") {
+        Some((element, pseudo)) => format_smolstr!("& :where({element}){NOT_PROSE}::{pseudo}"),
+        None => format_smolstr!("& :where({element}){NOT_PROSE}"),
+    };
+    Rule::new_with_decls(selector, decls.into_iter().collect())
+}
+
+fn default_rule(typography: &ThemeMap) -> Rule {
+    let mut decls =
+        vec![Decl::new("color", "var(--tw-prose-body)"), Decl::new("max-width", "65ch")];
+    for (key, _, _) in COLOR_SHADES {
+        let value = typography.get(key).unwrap_or_default();
+        let invert = typography.get(&format_smolstr!("invert-{key}")).unwrap_or_default();
+        decls.push(Decl::new(format_smolstr!("--tw-prose-{key}"), value));
+        decls.push(Decl::new(format_smolstr!("--tw-prose-invert-{key}"), invert));
+    }
+
+    let rules = BASE_STYLES
+        .iter()
+        .map(|(element, decls)| element_rule(element, decls.iter().map(|(k, v)| Decl::new(*k, *v))))
+        .collect();
+
+    Rule { selector: "&".into(), decls, rules: RuleList(rules) }
+}
+
+fn size_rule(size: &str) -> Rule {
+    let Some((_, [font_size, line_height, p, h1, h2, h3])) =
+        SIZES.iter().find(|(name, _)| *name == size)
+    else {
+        return Rule::default();
+    };
+
+    let margin = |v: &str| [Decl::new("margin-top", v), Decl::new("margin-bottom", v)];
+
+    Rule {
+        selector: "&".into(),
+        decls: vec![Decl::new("font-size", *font_size), Decl::new("line-height", *line_height)],
+        rules: RuleList(vec![
+            element_rule("p", margin(p)),
+            element_rule("h1", [Decl::new("font-size", *h1)]),
+            element_rule("h2", [Decl::new("font-size", *h2)]),
+            element_rule("h3", [Decl::new("font-size", *h3)]),
+        ]),
+    }
+}
+
+fn color_rule(gray: &str, colors: &ThemeMap) -> Rule {
+    let mut decls = Vec::with_capacity(COLOR_SHADES.len() * 2);
+    for (key, light, invert) in COLOR_SHADES {
+        decls.push(Decl::new(format_smolstr!("--tw-prose-{key}"), shade(colors, gray, light)));
+        decls.push(Decl::new(
+            format_smolstr!("--tw-prose-invert-{key}"),
+            shade(colors, gray, invert),
+        ));
+    }
+    Rule::new(decls)
+}
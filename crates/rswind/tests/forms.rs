@@ -0,0 +1,78 @@
+#![cfg(feature = "forms")]
+
+use rswind::{
+    config::GeneratorConfig,
+    plugin::PluginError,
+    preset::{builtin_plugins, tailwind_preset, tailwind_theme},
+    processor::GeneratorProcessor,
+    Generator,
+};
+use serde_json::json;
+
+fn processor(config: serde_json::Value) -> GeneratorProcessor {
+    Generator::builder()
+        .with_theme(tailwind_theme)
+        .with_preset(tailwind_preset)
+        .with_plugin_factories(builtin_plugins())
+        .with_config(GeneratorConfig::from_value(config).unwrap())
+        .build_processor()
+        .unwrap()
+}
+
+#[test]
+fn test_forms_base_strategy() {
+    let mut processor = processor(json!({
+        "plugins": [{ "name": "forms", "options": { "strategy": "base" } }]
+    }));
+
+    let css = processor.run_with(["flex", "form-input"]).css;
+
+    assert!(css.starts_with("[type='text'], input:where(:not([type])), [type='email']"));
+    assert!(css.contains("  border-color: #6b7280;\n"));
+    assert!(css.contains("  padding-top: 0.5rem;\n  padding-right: 0.75rem;\n"));
+    assert!(css.contains("[type='radio'] {\n  border-radius: 100%;\n}\n"));
+    assert!(css.ends_with(".flex {\n  display: flex;\n}\n"));
+    assert!(!css.contains(".form-input"));
+}
+
+#[test]
+fn test_forms_class_strategy() {
+    let mut processor = processor(json!({
+        "plugins": [{ "name": "forms", "options": { "strategy": "class" } }],
+        "theme": { "extend": { "colors": { "blue-600": "#0000ff" } } }
+    }));
+
+    let css = processor.run_with(["form-checkbox"]).css;
+
+    assert!(css.starts_with(".form-checkbox {\n  appearance: none;\n"));
+    assert!(css.contains("  color: #0000ff;\n"));
+    assert!(css.contains("  &:checked {\n    border-color: transparent;\n"));
+    assert!(!css.contains("[type='checkbox']"));
+}
+
+#[test]
+fn test_plugins_config() {
+    let mut processor = processor(json!({ "plugins": ["typography", "forms"] }));
+
+    let css = processor.run_with(["prose", "form-select"]).css;
+    assert!(css.starts_with("[type='text']"));
+    assert!(css.contains(".prose {\n"));
+    assert!(css.contains(".form-select {\n"));
+
+    let build = |config| {
+        Generator::builder()
+            .with_plugin_factories(builtin_plugins())
+            .with_config(GeneratorConfig::from_value(config).unwrap())
+            .build_processor()
+            .map(|_| ())
+    };
+
+    assert!(matches!(
+        build(json!({ "plugins": ["unknown"] })),
+        Err(rswind::generator::AppBuildError::PluginError(PluginError::Unknown(name))) if name == "unknown"
+    ));
+    assert!(matches!(
+        build(json!({ "plugins": [{ "name": "forms", "options": { "strategy": "none" } }] })),
+        Err(rswind::generator::AppBuildError::PluginError(PluginError::InvalidOptions { .. }))
+    ));
+}
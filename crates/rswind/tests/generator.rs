@@ -4,6 +4,7 @@ mod generator_tests {
 
     use rswind::{
         config::GeneratorConfig,
+        extract::{Extractable, Extractor},
         preset::{tailwind_preset, tailwind_theme},
         processor::ResultKind,
         Generator,
@@ -45,6 +46,21 @@ mod generator_tests {
         assert_eq!(generator.theme().get_value("colors", "red-500").as_deref(), Some("#ef4444"));
     }
 
+    #[test]
+    fn test_generator_utility_without_css() {
+        let res = Generator::builder()
+            .with_theme(tailwind_theme)
+            .with_preset(tailwind_preset)
+            .with_config(
+                GeneratorConfig::from_value(json!({ "utilities": [{ "key": "foo" }] })).unwrap(),
+            )
+            .build();
+        assert_eq!(
+            res.err().map(|e| e.to_string()).as_deref(),
+            Some("Failed to parse utility: Utility `foo` has no `css`")
+        );
+    }
+
     #[test]
     fn test_generator_builder_with_preset() {
         let generator = Generator::builder()
@@ -118,4 +134,146 @@ mod generator_tests {
         assert_eq!(&*res.css, ".flex {\n  display: flex;\n}\n");
         assert_eq!(res.kind, ResultKind::Generated);
     }
+
+    #[test]
+    fn test_config_utility_templates() {
+        let mut generator = Generator::builder()
+            .with_theme(tailwind_theme)
+            .with_preset(tailwind_preset)
+            .with_config(
+                GeneratorConfig::from_value(json!({
+                    "utilities": [
+                        {
+                            "key": "tab",
+                            "css": {
+                                "tab-size": "$0${1:+ / $1}",
+                                "&::after": {
+                                    "content": "'${raw}'"
+                                }
+                            },
+                            "theme": "spacing",
+                            "type": "number",
+                            "negative": true,
+                            "modifier": {
+                                "type": "number"
+                            }
+                        }
+                    ]
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        let res = generator.processor.run_with(["tab-4", "-tab-4", "tab-[3]/[2]"]);
+        assert_eq!(
+            &*res.css,
+            ".-tab-4 {\n  tab-size: calc(1rem * -1);\n  &::after {\n    content: '4';\n  }\n}\n.tab-4 {\n  tab-size: 1rem;\n  &::after {\n    content: '4';\n  }\n}\n.tab-\\[3\\]\\/\\[2\\] {\n  tab-size: 3 / 2;\n  &::after {\n    content: '3';\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_class_functions_extraction() {
+        let mut generator = Generator::builder()
+            .with_theme(tailwind_theme)
+            .with_preset(tailwind_preset)
+            .with_config(
+                GeneratorConfig::from_value(json!({
+                    "extract": {
+                        "classFunctions": {
+                            "functions": ["clsx"]
+                        }
+                    }
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        let input = r#"
+            const title = "block hidden"
+            const App = () => <div className={clsx('flex', cond && 'grid')}>{title}</div>
+        "#;
+        let candidates = Extractor::new(input, "tsx").with_options(&generator.extract).extract();
+        let res = generator.processor.run_with(candidates);
+
+        assert_eq!(&*res.css, ".flex {\n  display: flex;\n}\n.grid {\n  display: grid;\n}\n");
+    }
+
+    #[test]
+    fn test_custom_extractor_and_transforms() {
+        let mut generator = Generator::builder()
+            .with_theme(tailwind_theme)
+            .with_preset(tailwind_preset)
+            .with_config(
+                GeneratorConfig::from_value(json!({
+                    "extract": {
+                        "transforms": [{ "stripPrefix": "tw:" }]
+                    }
+                }))
+                .unwrap(),
+            )
+            .with_extractor("tpl", |input| {
+                input
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("@class "))
+                    .map(Into::into)
+                    .collect()
+            })
+            .build()
+            .unwrap();
+
+        let input = "@class tw:flex\n@title grid\n@class italic";
+        let candidates = generator
+            .extractors
+            .extractor(("page.tpl", input).into())
+            .with_options(&generator.extract)
+            .extract();
+        let res = generator.processor.run_with(candidates);
+
+        assert_eq!(
+            &*res.css,
+            ".flex {\n  display: flex;\n}\n.italic {\n  font-style: italic;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_theme_function_in_arbitrary_values() {
+        let mut generator = Generator::builder()
+            .with_theme(tailwind_theme)
+            .with_preset(tailwind_preset)
+            .build()
+            .unwrap();
+
+        let res = generator.processor.run_with([
+            "w-[calc(theme(spacing.4)_+_2px)]",
+            "[color:theme(colors.blue.500/50%)]",
+            "h-[theme(spacing.nope)]",
+        ]);
+
+        assert_eq!(
+            &*res.css,
+            ".w-\\[calc\\(theme\\(spacing\\.4\\)_\\+_2px\\)\\] {\n  width: calc(1rem + 2px);\n}\n\
+             .\\[color\\:theme\\(colors\\.blue\\.500\\/50\\%\\)\\] {\n  color: color-mix(in srgb, #3b82f6 50%, transparent);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_split_candidate() {
+        let generator = Generator::builder()
+            .with_theme(tailwind_theme)
+            .with_preset(tailwind_preset)
+            .build()
+            .unwrap();
+        let design = &generator.processor.design;
+
+        let split = |candidate| {
+            design.split_candidate(candidate).map(|(variants, key)| (variants.to_vec(), key))
+        };
+        assert_eq!(split("flex"), Some((vec![], "flex")));
+        assert_eq!(split("md:hover:bg-red-500"), Some((vec!["md", "hover"], "bg")));
+        assert_eq!(split("-mt-[3px]"), Some((vec![], "mt")));
+        assert_eq!(split("[color:red]"), Some((vec![], "color")));
+        assert_eq!(split("not-a-utility"), None);
+    }
 }
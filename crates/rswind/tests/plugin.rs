@@ -0,0 +1,114 @@
+use rswind::{
+    config::GeneratorConfig,
+    css::{Decl, DeclList, Rule},
+    parse::UtilityBuilder,
+    plugin::Plugin,
+    preset::{tailwind_preset, tailwind_theme},
+    processor::GeneratorProcessor,
+    DesignSystem,
+};
+use smol_str::SmolStr;
+
+struct Brand;
+
+impl Plugin for Brand {
+    fn name(&self) -> &str {
+        "brand"
+    }
+
+    fn configure(&self, config: &mut GeneratorConfig) {
+        config.content.push("./**/*.brand".into());
+    }
+
+    fn theme(&self, design: &mut DesignSystem) {
+        let mut theme = GeneratorConfig::from_value(serde_json::json!({
+            "theme": { "extend": { "colors": { "brand": "#ff0000" } } }
+        }))
+        .unwrap()
+        .theme;
+        design.theme.merge(&mut theme);
+    }
+
+    fn utilities(&self, design: &mut DesignSystem) {
+        design.add_static("brand", DeclList::from([Decl::new("color", "#ff0000")]));
+    }
+
+    fn variants(&self, design: &mut DesignSystem) {
+        design.add_variant("brand-hover", ["&:hover"]);
+    }
+
+    fn transform_candidate(&self, candidate: &str) -> Option<SmolStr> {
+        candidate.strip_prefix("x-").map(Into::into)
+    }
+
+    fn transform_css(&self, css: &mut String) {
+        css.insert_str(0, "/* brand */\n");
+    }
+}
+
+struct Uses;
+
+impl Plugin for Uses {
+    fn name(&self) -> &str {
+        "uses"
+    }
+
+    fn dependencies(&self) -> &[&str] {
+        &["brand"]
+    }
+}
+
+fn processor() -> GeneratorProcessor {
+    GeneratorProcessor::builder()
+        .with_theme(tailwind_theme)
+        .with_preset(tailwind_preset)
+        .with_plugin(Uses)
+        .with_plugin(Brand)
+        .build_processor()
+        .unwrap()
+}
+
+#[test]
+fn test_plugin_hooks() {
+    let mut processor = processor();
+
+    assert_eq!(processor.design.theme.get_value("colors", "brand").as_deref(), Some("#ff0000"));
+
+    let res = processor.run_with(["x-brand", "brand-hover:text-brand"]);
+    assert_eq!(
+        res.css.as_str(),
+        "/* brand */\n.brand {\n  color: #ff0000;\n}\n.brand-hover\\:text-brand:hover {\n  color: #ff0000;\n}\n"
+    );
+}
+
+#[test]
+fn test_plugin_configure() {
+    let generator = rswind::Generator::builder().with_plugin(Brand).build().unwrap();
+
+    assert!(generator.glob.is_match(&generator.base().join("index.brand")));
+}
+
+struct Tab;
+
+impl Plugin for Tab {
+    fn name(&self) -> &str {
+        "tab"
+    }
+
+    fn utilities(&self, design: &mut DesignSystem) {
+        let builder = UtilityBuilder::new_optional("tab", |_, value| {
+            value.parse::<u8>().ok().map(|_| Rule::new([Decl::new("tab-size", value)]))
+        });
+        let (key, utility) = builder.parse(&design.theme).unwrap();
+        design.add_utility(&key, utility);
+    }
+}
+
+#[test]
+fn test_optional_utility() {
+    let mut processor = GeneratorProcessor::builder().with_plugin(Tab).build_processor().unwrap();
+
+    let res = processor.run_with(["tab-[4]", "tab-[auto]"]);
+    assert_eq!(res.css.as_str(), ".tab-\\[4\\] {\n  tab-size: 4;\n}\n");
+    assert_eq!(processor.run_with(["tab-[auto]"]).css.as_str(), "");
+}
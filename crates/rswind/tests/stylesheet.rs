@@ -0,0 +1,98 @@
+use rswind::{
+    preset::{tailwind_preset, tailwind_theme},
+    processor::GeneratorProcessor,
+    stylesheet::{Stylesheet, StylesheetError},
+};
+
+fn processor() -> GeneratorProcessor {
+    GeneratorProcessor::builder()
+        .with_theme(tailwind_theme)
+        .with_preset(tailwind_preset)
+        .build_processor()
+        .unwrap()
+}
+
+#[test]
+fn test_stylesheet() {
+    let mut processor = processor();
+    let input = r#"@tailwind base;
+@tailwind components;
+@tailwind utilities;
+
+.card {
+  color: theme('colors.blue.500');
+}
+
+@layer components {
+  .btn {
+    @apply px-4 hover:underline;
+  }
+}
+"#;
+    let sheet = Stylesheet::parse(input, &processor.design).unwrap();
+    let css = processor.run_with(["flex"]).css;
+    let res = sheet.render(&processor.design, &css);
+    assert_eq!(
+        res,
+        r#"
+.btn {
+  padding-left: 1rem;
+  padding-right: 1rem;
+}
+.btn:hover {
+  text-decoration: underline;
+}
+
+.flex {
+  display: flex;
+}
+
+
+.card {
+  color: #3b82f6;
+}
+
+"#
+    );
+}
+
+#[test]
+fn test_unknown_utility() {
+    let processor = processor();
+    let res = Stylesheet::parse(".a { @apply not-a-utility; }", &processor.design);
+    assert!(matches!(res, Err(StylesheetError::UnknownUtility { .. })));
+}
+
+#[test]
+fn test_import_and_important() {
+    let mut processor = processor();
+    let input = "@import \"rswind\";\n\n@media print {\n  .nav {\n    @apply hidden md:flex !important;\n  }\n}\n";
+    let sheet = Stylesheet::parse(input, &processor.design).unwrap();
+    let css = processor.run_with(["block"]).css;
+    assert_eq!(
+        sheet.render(&processor.design, &css),
+        r#".block {
+  display: block;
+}
+
+
+@media print {
+  .nav {
+    display: none !important;
+  }
+  @media (width >= 768px) {
+    .nav {
+      display: flex !important;
+    }
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn test_unknown_theme_value() {
+    let processor = processor();
+    let res = Stylesheet::parse(".a { color: theme(colors.nope); }", &processor.design);
+    assert_eq!(res.unwrap_err().to_string(), "Unknown theme value: theme(colors.nope)");
+}
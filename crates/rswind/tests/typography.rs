@@ -0,0 +1,77 @@
+#![cfg(feature = "typography")]
+
+use rswind::{
+    config::GeneratorConfig,
+    preset::{tailwind_preset, tailwind_theme, typography_preset},
+    processor::GeneratorProcessor,
+    Generator,
+};
+use serde_json::json;
+
+fn processor(config: serde_json::Value) -> GeneratorProcessor {
+    Generator::builder()
+        .with_theme(tailwind_theme)
+        .with_preset(tailwind_preset)
+        .with_preset(typography_preset)
+        .with_config(GeneratorConfig::from_value(config).unwrap())
+        .build_processor()
+        .unwrap()
+}
+
+#[test]
+fn test_prose() {
+    let mut processor = processor(json!({}));
+    let css = processor.run_with(["prose"]).css;
+
+    assert!(css.starts_with(".prose {\n  color: var(--tw-prose-body);\n  max-width: 65ch;\n"));
+    assert!(css.contains("  --tw-prose-body: #374151;\n"));
+    assert!(css.contains("  --tw-prose-invert-headings: #fff;\n"));
+    assert!(css.contains(
+        "  & :where(p):not(:where([class~=\"not-prose\"],[class~=\"not-prose\"] *)) {\n    margin-top: 1.25em;\n"
+    ));
+    assert!(css.contains(
+        "  & :where(code):not(:where([class~=\"not-prose\"],[class~=\"not-prose\"] *))::before {\n    content: \"`\";\n"
+    ));
+}
+
+#[test]
+fn test_prose_modifiers() {
+    let mut processor = processor(json!({}));
+    let css = processor.run_with(["prose-lg", "prose-slate", "prose-invert", "max-w-none"]).css;
+
+    let lg = css.find(".prose-lg {\n  font-size: 1.125rem;\n  line-height: 1.7777778;\n").unwrap();
+    let slate = css.find(".prose-slate {\n  --tw-prose-body: #334155;\n").unwrap();
+    let invert =
+        css.find(".prose-invert {\n  --tw-prose-body: var(--tw-prose-invert-body);\n").unwrap();
+    let max_w = css.find(".max-w-none {").unwrap();
+
+    assert!(lg < slate && slate < invert && invert < max_w);
+}
+
+#[test]
+fn test_prose_element_variants() {
+    let mut processor = processor(json!({}));
+
+    assert_eq!(
+        processor.run_with(["prose-headings:underline"]).css.as_str(),
+        ".prose-headings\\:underline :is(:where(h1, h2, h3, h4, h5, h6, th):not(:where([class~=\"not-prose\"],[class~=\"not-prose\"] *))) {\n  text-decoration: underline;\n}\n"
+    );
+}
+
+#[test]
+fn test_prose_theme() {
+    let mut processor = processor(json!({
+        "theme": {
+            "extend": {
+                "typography": {
+                    "links": "#0ea5e9"
+                }
+            }
+        }
+    }));
+
+    let css = processor.run_with(["prose"]).css;
+
+    assert!(css.contains("  --tw-prose-links: #0ea5e9;\n"));
+    assert!(css.contains("  --tw-prose-body: #374151;\n"));
+}
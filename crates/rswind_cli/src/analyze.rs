@@ -0,0 +1,257 @@
+use std::{
+    fmt::Write,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use rswind::{generator::Generator, processor::GenerateResult};
+use rswind_css::ToCssString;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use serde::Serialize;
+
+use crate::{content::Content, output::OutputFormat};
+
+#[derive(Debug, Parser)]
+pub struct AnalyzeCommand {
+    #[arg(
+        long,
+        help = "Number of utility families, variants and files listed",
+        default_value_t = 10
+    )]
+    pub top: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub files: usize,
+    /// Unique candidates extracted from all files
+    pub candidates: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub css_bytes: usize,
+    /// Utility families by bytes of generated css
+    pub utilities: Vec<UtilityStats>,
+    /// Variants by number of utilities using them
+    pub variants: Vec<Count>,
+    /// Files by number of unique valid classes
+    pub files_by_classes: Vec<Count>,
+    /// Where valid classes in JavaScript and TypeScript come from, e.g. `className` or `clsx()`,
+    /// by number of unique classes
+    pub origins: Vec<Count>,
+    pub timings: Timings,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UtilityStats {
+    pub key: String,
+    pub count: usize,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Count {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Time spent in each phase in milliseconds
+#[derive(Debug, Serialize)]
+pub struct Timings {
+    pub walk: f64,
+    pub extract: f64,
+    pub generate: f64,
+    pub serialize: f64,
+}
+
+/// Generate the content files like [`Generator::generate_contents`], recording statistics
+///
+/// Each phase of the generator is run and timed separately,
+/// the utilities are measured before their css is written
+pub fn generate_with_stats(
+    app: &mut Generator,
+    content: Content,
+    top: usize,
+) -> (GenerateResult, Stats) {
+    let start = Instant::now();
+    let paths = match content.walk {
+        true => app.walk_contents(),
+        false => Vec::new(),
+    };
+    let walk_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut files = app.read_files(paths);
+    files.extend(content.stdin);
+    let extracted = app.extractors.extract_files(&files, &app.extract, app.iter_kind());
+    let candidates = extracted.iter().flatten().map(AsRef::as_ref).collect::<HashSet<&str>>();
+    let extract_time = start.elapsed();
+
+    let start = Instant::now();
+    let utilities = app.processor.generate(&candidates);
+    let generate_time = start.elapsed();
+
+    let processor = &app.processor;
+    let mut families = HashMap::<&str, UtilityStats>::default();
+    let mut variants = HashMap::<&str, usize>::default();
+    for utility in &utilities {
+        let (vs, family) = processor
+            .design
+            .split_candidate(&utility.raw)
+            .unwrap_or_else(|| (Default::default(), &utility.raw));
+        let stats = families.entry(family).or_insert_with(|| UtilityStats {
+            key: family.to_owned(),
+            count: 0,
+            bytes: 0,
+        });
+        stats.count += 1;
+        stats.bytes += utility.rule.to_css_string().len();
+        for v in vs {
+            *variants.entry(v).or_default() += 1;
+        }
+    }
+
+    let generated = utilities.iter().map(|u| u.raw.as_str()).collect::<HashSet<_>>();
+    let is_valid = |c: &str| generated.contains(processor.plugins.transform_candidate(c).as_ref());
+
+    let base = app.glob.base();
+    let files_by_classes = files
+        .iter()
+        .zip(&extracted)
+        .map(|(f, candidates)| Count {
+            name: display_path(f.path(), base),
+            count: candidates.iter().filter(|c| is_valid(c)).count(),
+        })
+        .filter(|c| c.count > 0)
+        .collect();
+
+    let (extractors, extract) = (&app.extractors, &app.extract);
+    let mut origins = HashMap::<String, HashSet<_>>::default();
+    for f in &files {
+        for (candidate, origin) in extractors.extractor(f.into()).with_options(extract).origins() {
+            if is_valid(&candidate) {
+                origins.entry(origin.to_string()).or_default().insert(candidate);
+            }
+        }
+    }
+    let origins = origins
+        .into_iter()
+        .map(|(name, candidates)| Count { name, count: candidates.len() })
+        .collect();
+
+    let valid = candidates.iter().filter(|c| is_valid(c)).count();
+    let mut utility_stats = families.into_values().collect::<Vec<_>>();
+    utility_stats.sort_unstable_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.key.cmp(&b.key)));
+    utility_stats.truncate(top);
+
+    let variants =
+        variants.into_iter().map(|(name, count)| Count { name: name.to_owned(), count }).collect();
+
+    let start = Instant::now();
+    let res = app.processor.generate_css(utilities);
+    let serialize_time = start.elapsed();
+
+    let stats = Stats {
+        files: files.len(),
+        candidates: candidates.len(),
+        valid,
+        invalid: candidates.len() - valid,
+        css_bytes: res.css.len(),
+        utilities: utility_stats,
+        variants: sort_counts(variants, top),
+        files_by_classes: sort_counts(files_by_classes, top),
+        origins: sort_counts(origins, top),
+        timings: Timings {
+            walk: millis(walk_time),
+            extract: millis(extract_time),
+            generate: millis(generate_time),
+            serialize: millis(serialize_time),
+        },
+    };
+
+    (res, stats)
+}
+
+fn sort_counts(mut counts: Vec<Count>, top: usize) -> Vec<Count> {
+    counts.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    counts.truncate(top);
+    counts
+}
+
+fn display_path(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base).unwrap_or(path).display().to_string()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl Stats {
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format.is_json() {
+            false => self.table(),
+            true => serde_json::to_string_pretty(self).unwrap(),
+        }
+    }
+
+    fn table(&self) -> String {
+        let mut res = String::new();
+        let summary = [
+            ("Files scanned", self.files.to_string()),
+            ("Candidates extracted", self.candidates.to_string()),
+            ("Valid candidates", self.valid.to_string()),
+            ("Invalid candidates", self.invalid.to_string()),
+            ("CSS size", format!("{} B", self.css_bytes)),
+        ];
+        table(&mut res, "Summary", &summary);
+
+        let utilities = self
+            .utilities
+            .iter()
+            .map(|u| (u.key.as_str(), format!("{} B ({})", u.bytes, u.count)))
+            .collect::<Vec<_>>();
+        table(&mut res, "Utilities", &utilities);
+
+        for (title, counts) in [
+            ("Variants", &self.variants),
+            ("Files", &self.files_by_classes),
+            ("Origins", &self.origins),
+        ] {
+            let counts =
+                counts.iter().map(|c| (c.name.as_str(), c.count.to_string())).collect::<Vec<_>>();
+            table(&mut res, title, &counts);
+        }
+
+        let t = &self.timings;
+        let timings = [
+            ("walk", t.walk),
+            ("extract", t.extract),
+            ("generate", t.generate),
+            ("serialize", t.serialize),
+        ]
+        .map(|(phase, ms)| (phase, format!("{ms:.2} ms")));
+        table(&mut res, "Timings", &timings);
+
+        res
+    }
+}
+
+/// Write a titled table of two columns, values aligned to the right
+fn table(dest: &mut String, title: &str, rows: &[(&str, String)]) {
+    if rows.is_empty() {
+        return;
+    }
+
+    let key_width = rows.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    let value_width = rows.iter().map(|(_, v)| v.len()).max().unwrap_or(0);
+
+    if !dest.is_empty() {
+        dest.push('\n');
+    }
+    let _ = writeln!(dest, "{title}");
+    let _ = writeln!(dest, "{}", "-".repeat(key_width + value_width + 2));
+    for (key, value) in rows {
+        let _ = writeln!(dest, "{key:<key_width$}  {value:>value_width$}");
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::{self, Read};
+
+use clap::ValueEnum;
+use rswind::{generator::Generator, io::FileInput};
+
+use crate::CliError;
+
+/// The positional content reading a document from stdin
+pub static STDIN: &str = "-";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum StdinKind {
+    #[default]
+    Html,
+    Ecma,
+}
+
+impl StdinKind {
+    /// The path of the document, its extension picks the extractor
+    fn file_name(&self) -> &'static str {
+        match self {
+            Self::Html => "stdin.html",
+            Self::Ecma => "stdin.js",
+        }
+    }
+}
+
+/// The documents to generate from, content files and a document from stdin
+pub struct Content {
+    pub stdin: Option<FileInput>,
+    /// Whether files matched by `content` are read, not if only stdin is given
+    pub walk: bool,
+}
+
+impl Content {
+    pub fn new(content: &[String], stdin_kind: StdinKind) -> Result<Self, CliError> {
+        let stdin = match content.iter().any(|c| c == STDIN) {
+            true => {
+                let mut document = String::new();
+                io::stdin().read_to_string(&mut document).map_err(CliError::Stdin)?;
+                Some(FileInput::new(stdin_kind.file_name(), document))
+            }
+            false => None,
+        };
+        let walk = stdin.is_none() || content.iter().any(|c| c != STDIN);
+        Ok(Self { stdin, walk })
+    }
+
+    /// Read the content files if needed, followed by the document from stdin
+    pub fn read(self, app: &Generator) -> Vec<FileInput> {
+        let mut files = match self.walk {
+            true => app.read_contents(),
+            false => Vec::new(),
+        };
+        files.extend(self.stdin);
+        files
+    }
+}
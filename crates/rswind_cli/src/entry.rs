@@ -0,0 +1,51 @@
+use std::{borrow::Cow, fs, path::PathBuf};
+
+use rswind::{
+    design::DesignSystem,
+    generator::AppBuildError,
+    io::{write_output, IoError, OutputChannel},
+    stylesheet::Stylesheet,
+};
+
+/// An output, with the input stylesheet the generated CSS is inserted into if any
+pub struct Entry {
+    pub input: Option<PathBuf>,
+    pub output: OutputChannel,
+    stylesheet: Option<Stylesheet>,
+}
+
+impl Entry {
+    pub fn new(output: OutputChannel) -> Self {
+        Self { input: None, output, stylesheet: None }
+    }
+
+    pub fn with_input(
+        input: PathBuf,
+        output: OutputChannel,
+        design: &DesignSystem,
+    ) -> Result<Self, AppBuildError> {
+        let mut entry = Self { input: Some(input.canonicalize()?), output, stylesheet: None };
+        entry.reload(design)?;
+        Ok(entry)
+    }
+
+    /// Read and process the input stylesheet again
+    pub fn reload(&mut self, design: &DesignSystem) -> Result<(), AppBuildError> {
+        if let Some(input) = &self.input {
+            self.stylesheet = Some(Stylesheet::parse(&fs::read_to_string(input)?, design)?);
+        }
+        Ok(())
+    }
+
+    /// The generated CSS, inserted into the input stylesheet if any
+    pub fn render<'a>(&self, design: &DesignSystem, css: &'a str) -> Cow<'a, str> {
+        match &self.stylesheet {
+            Some(stylesheet) => Cow::Owned(stylesheet.render(design, css)),
+            None => Cow::Borrowed(css),
+        }
+    }
+
+    pub fn write(&self, design: &DesignSystem, css: &str) -> Result<(), IoError> {
+        write_output(&self.render(design, css), &self.output)
+    }
+}
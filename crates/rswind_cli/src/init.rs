@@ -0,0 +1,214 @@
+use std::{
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use rswind::glob::DEFAULT_GLOB;
+use serde_json::json;
+use thiserror::Error;
+
+/// JSON schema of the config, published with `@rswind/cli` so it resolves without `node_modules`
+pub static SCHEMA_PATH: &str = "https://unpkg.com/@rswind/cli/schema.json";
+
+static INPUT_CSS: &str = "@tailwind base;\n@tailwind components;\n@tailwind utilities;\n";
+
+#[derive(Debug, Parser)]
+pub struct InitCommand {
+    #[arg(short, long, help = "Overwrite existing files")]
+    pub force: bool,
+
+    #[arg(
+        long,
+        help = "Create an input CSS file and add it to `stylesheets`",
+        num_args = 0..=1,
+        default_missing_value = "src/input.css"
+    )]
+    pub css: Option<String>,
+
+    #[arg(long, value_enum, help = "Format of the config file", default_value_t)]
+    pub config_format: ConfigFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ConfigFormat {
+    #[default]
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Json => "rswind.config.json",
+            Self::Toml => "rswind.config.toml",
+            Self::Yaml => "rswind.config.yaml",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum InitError {
+    #[error("{0} already exists, pass `--force` to overwrite it")]
+    AlreadyExists(PathBuf),
+    #[error("Failed to write {0}: {1}")]
+    Io(PathBuf, io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framework {
+    Next,
+    Vue,
+    Vite,
+    Leptos,
+}
+
+impl Framework {
+    /// Detect the framework from `package.json` or `Cargo.toml` in `cwd`
+    pub fn detect(cwd: &Path) -> Option<Self> {
+        if let Ok(package) = fs::read_to_string(cwd.join("package.json")) {
+            let package: serde_json::Value = serde_json::from_str(&package).ok()?;
+            let has_dep = |name: &str| {
+                ["dependencies", "devDependencies"]
+                    .iter()
+                    .any(|deps| package.get(deps).and_then(|deps| deps.get(name)).is_some())
+            };
+            return [("next", Self::Next), ("vue", Self::Vue), ("vite", Self::Vite)]
+                .into_iter()
+                .find_map(|(name, framework)| has_dep(name).then_some(framework));
+        }
+
+        let manifest = fs::read_to_string(cwd.join("Cargo.toml")).ok()?;
+        manifest
+            .lines()
+            .map(str::trim_start)
+            .any(|line| {
+                line.strip_prefix("leptos")
+                    .is_some_and(|rest| rest.trim_start().starts_with(['=', '.']))
+            })
+            .then_some(Self::Leptos)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Next => "Next.js",
+            Self::Vue => "Vue",
+            Self::Vite => "Vite",
+            Self::Leptos => "Leptos",
+        }
+    }
+
+    pub fn content(&self) -> &'static [&'static str] {
+        match self {
+            Self::Next => &[
+                "./app/**/*.{js,jsx,ts,tsx,mdx}",
+                "./pages/**/*.{js,jsx,ts,tsx,mdx}",
+                "./components/**/*.{js,jsx,ts,tsx,mdx}",
+                "./src/**/*.{js,jsx,ts,tsx,mdx}",
+            ],
+            Self::Vue => &["./index.html", "./src/**/*.{vue,js,jsx,ts,tsx}"],
+            Self::Vite => &["./index.html", "./src/**/*.{js,jsx,ts,tsx}"],
+            Self::Leptos => &["./index.html", "./src/**/*.rs"],
+        }
+    }
+}
+
+/// Write a config file, and an input CSS file if `--css` is given, into `cwd`
+pub fn init(cwd: &Path, cmd: &InitCommand) -> Result<(), InitError> {
+    let format = cmd.config_format;
+    let framework = Framework::detect(cwd);
+    let content = framework.map_or(DEFAULT_GLOB, |f| f.content());
+    let stylesheet = cmd.css.as_deref().map(|input| {
+        let name = Path::new(input).file_name().and_then(|n| n.to_str()).unwrap_or("output.css");
+        (input, format!("dist/{name}"))
+    });
+
+    let config_path = cwd.join(format.file_name());
+    let css_path = cmd.css.as_ref().map(|input| cwd.join(input));
+    for path in [Some(&config_path), css_path.as_ref()].into_iter().flatten() {
+        if path.exists() && !cmd.force {
+            return Err(InitError::AlreadyExists(path.clone()));
+        }
+    }
+
+    let stylesheet = stylesheet.as_ref().map(|(input, output)| (*input, output.as_str()));
+    write_file(&config_path, &render_config(format, content, stylesheet))?;
+    if let Some(css_path) = &css_path {
+        write_file(css_path, INPUT_CSS)?;
+    }
+
+    if let Some(framework) = framework {
+        println!("Detected {}", framework.name().cyan());
+    }
+    println!("Created {}", config_path.display().to_string().green());
+    if let Some(css_path) = &css_path {
+        println!("Created {}", css_path.display().to_string().green());
+    }
+
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), InitError> {
+    let write = || {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)
+    };
+    write().map_err(|e| InitError::Io(path.to_owned(), e))
+}
+
+fn render_config(
+    format: ConfigFormat,
+    content: &[&str],
+    stylesheet: Option<(&str, &str)>,
+) -> String {
+    // JSON strings are valid TOML basic strings and YAML double-quoted strings
+    let quote = |s: &str| serde_json::to_string(s).unwrap();
+    let mut res = String::new();
+
+    match format {
+        ConfigFormat::Json => {
+            let mut config = json!({ "$schema": SCHEMA_PATH, "content": content });
+            if let Some((input, output)) = stylesheet {
+                config["stylesheets"] = json!([{ "input": input, "output": output }]);
+            }
+            res = serde_json::to_string_pretty(&config).unwrap();
+            res.push('\n');
+        }
+        ConfigFormat::Toml => {
+            let _ = writeln!(res, "#:schema {SCHEMA_PATH}\n\ncontent = [");
+            for glob in content {
+                let _ = writeln!(res, "  {},", quote(glob));
+            }
+            res.push_str("]\n");
+            if let Some((input, output)) = stylesheet {
+                let _ = write!(
+                    res,
+                    "\n[[stylesheets]]\ninput = {}\noutput = {}\n",
+                    quote(input),
+                    quote(output)
+                );
+            }
+        }
+        ConfigFormat::Yaml => {
+            let _ = writeln!(res, "# yaml-language-server: $schema={SCHEMA_PATH}\n\ncontent:");
+            for glob in content {
+                let _ = writeln!(res, "  - {}", quote(glob));
+            }
+            if let Some((input, output)) = stylesheet {
+                let _ = write!(
+                    res,
+                    "\nstylesheets:\n  - input: {}\n    output: {}\n",
+                    quote(input),
+                    quote(output)
+                );
+            }
+        }
+    }
+
+    res
+}
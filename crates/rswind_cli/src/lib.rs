@@ -1,19 +1,39 @@
-use std::{ffi::OsString, path::PathBuf};
+use std::{
+    ffi::OsString,
+    io, mem,
+    path::{Path, PathBuf},
+};
 
 use clap::{command, Parser};
 use colored::Colorize;
 use rswind::{
-    config::GeneratorConfig,
-    generator::AppBuildError,
-    io::{write_output, OutputChannel},
-    preset::{tailwind_preset, tailwind_theme},
+    config::{load::ConfigFile, GeneratorConfigError, StylesheetConfig},
+    design::DesignSystem,
+    generator::{AppBuildError, Generator},
+    glob::escape_file,
+    io::{write_output, IoError, OutputChannel},
+    preset::{builtin_plugins, tailwind_preset, tailwind_theme},
     processor::GeneratorProcessor,
 };
 use rswind_css::ToCssString;
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use serde_json::json;
+use thiserror::Error;
+use tracing_subscriber::{
+    filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+};
 
+use analyze::{generate_with_stats, AnalyzeCommand};
+use content::{Content, StdinKind, STDIN};
+use entry::Entry;
+use init::{init, InitCommand, InitError};
+use output::{DiagnosticCollector, OutputFormat};
 use watch::WatchApp;
 
+mod analyze;
+mod content;
+mod entry;
+mod init;
+mod output;
 mod watch;
 
 #[derive(Debug, Parser)]
@@ -22,21 +42,43 @@ pub struct Opts {
     #[command(subcommand)]
     pub cmd: Option<SubCommand>,
 
+    #[arg(help = "Content files or globs, replacing `content` in config, `-` reads stdin")]
     pub content: Vec<String>,
 
-    #[arg(short, help = "Output path", default_value_t = OutputChannel::Stdout)]
-    pub output: OutputChannel,
+    #[arg(long, value_enum, help = "Kind of the document read from stdin", default_value_t)]
+    pub stdin_kind: StdinKind,
+
+    #[arg(short, help = "Output path, `-` for stdout [default: -]")]
+    pub output: Option<OutputChannel>,
+
+    #[arg(short, long, help = "Input CSS file, generated CSS is inserted at `@tailwind` markers")]
+    pub input: Option<String>,
 
-    #[arg(short, long, default_value_t = false, help = "Enable watch mode")]
+    #[arg(
+        short,
+        long,
+        default_value_t = false,
+        help = "Enable watch mode",
+        conflicts_with = "format"
+    )]
     pub watch: bool,
 
+    #[arg(long, help = "Print build statistics to stderr", conflicts_with = "watch")]
+    pub stats: bool,
+
+    #[arg(long, value_enum, help = "Output format", default_value_t, global = true)]
+    pub format: OutputFormat,
+
     #[arg(short, long, help = "Enable strict mode")]
     pub strict: bool,
 
-    #[arg(long, help = "Path to config file", default_value = "rswind.config.json")]
-    pub config: String,
+    #[arg(
+        long,
+        help = "Path to config file, searched in the working directory and its parents by default"
+    )]
+    pub config: Option<String>,
 
-    #[arg(short, long, help = "Path to working directory", default_value = ".")]
+    #[arg(short, long, help = "Path to working directory", default_value = ".", global = true)]
     pub cwd: String,
 }
 
@@ -44,6 +86,7 @@ pub struct Opts {
 pub enum SubCommand {
     Debug(DebugCommand),
     Init(InitCommand),
+    Analyze(AnalyzeCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -54,60 +97,228 @@ pub struct DebugCommand {
     pub print_ast: bool,
 }
 
-#[derive(Debug, Parser)]
-pub struct InitCommand {}
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error(transparent)]
+    Build(#[from] AppBuildError),
+    #[error(transparent)]
+    Config(#[from] GeneratorConfigError),
+    #[error(transparent)]
+    Init(#[from] InitError),
+    #[error(transparent)]
+    Io(#[from] IoError),
+    #[error("Failed to read stdin: {0}")]
+    Stdin(io::Error),
+    #[error("Content from stdin can not be watched")]
+    WatchStdin,
+    #[error("`-o` requires `-i` when `stylesheets` is set in config")]
+    OutputWithStylesheets,
+}
 
-pub fn cli<I>(args: I) -> Result<(), AppBuildError>
+pub fn cli<I>(args: I) -> Result<(), CliError>
 where
     I: IntoIterator,
     I::Item: Into<OsString> + Clone,
 {
+    let opts = Opts::parse_from(args);
+    let format = opts.output_format();
+
+    // logs go to stderr, stdout is kept for CSS or JSON
+    let diagnostics = DiagnosticCollector::default();
     tracing_subscriber::registry()
-        .with(fmt::layer())
-        .with(EnvFilter::from_env("RSWIND_LOG"))
+        .with(fmt::layer().with_writer(io::stderr).with_filter(EnvFilter::from_env("RSWIND_LOG")))
+        .with(format.is_json().then(|| diagnostics.clone().with_filter(LevelFilter::WARN)))
         .init();
 
-    let mut opts = Opts::parse_from(args);
+    let res = run(opts, &diagnostics);
+    if let (Err(e), OutputFormat::Json) = (&res, format) {
+        println!("{}", e.to_json());
+    }
+    res
+}
 
-    let mut app = GeneratorProcessor::builder()
-        .with_theme(tailwind_theme)
-        .with_preset(tailwind_preset)
-        .with_config(GeneratorConfig::from_file(&opts.config)?)
-        .with_watch(opts.watch)
-        .with_base(Some(opts.cwd.clone()))
-        .build()?;
+impl Opts {
+    /// The output format of the command to run, `init` only writes text
+    fn output_format(&self) -> OutputFormat {
+        match &self.cmd {
+            Some(SubCommand::Init(_)) => OutputFormat::Text,
+            _ => self.format,
+        }
+    }
+}
+
+fn run(opts: Opts, diagnostics: &DiagnosticCollector) -> Result<(), CliError> {
+    let cwd = PathBuf::from(&opts.cwd);
 
-    if let OutputChannel::FileSystem(path) = opts.output {
-        opts.output = OutputChannel::FileSystem(PathBuf::from(&opts.cwd).join(path))
+    if let Some(SubCommand::Init(cmd)) = &opts.cmd {
+        return Ok(init(&cwd, cmd)?);
+    }
+    // checked before stdin is read, which would block until it is closed
+    if opts.watch && opts.content.iter().any(|c| c == STDIN) {
+        return Err(CliError::WatchStdin);
     }
 
-    match opts.cmd {
+    let (mut app, config_files, stylesheets) = build_app(&opts)?;
+    let content = Content::new(&opts.content, opts.stdin_kind)?;
+
+    let entries = build_entries(&opts, stylesheets, &app.processor.design)?;
+
+    match &opts.cmd {
         None if opts.watch => {
-            app.watch(&opts.output);
+            let rebuild = || {
+                let (app, config_files, stylesheets) = build_app(&opts)?;
+                let entries = build_entries(&opts, stylesheets, &app.processor.design)?;
+                Ok((app, config_files, entries))
+            };
+            app.watch(entries, config_files, rebuild);
         }
-        None => {
-            let res = app.generate_contents();
-            write_output(&res.css, &opts.output);
+        None if opts.format.is_json() => {
+            let (res, stats) = generate_with_stats(&mut app, content, 10);
+            let design = &app.processor.design;
+            // CSS for stdout is only part of the JSON
+            let mut outputs = Vec::new();
+            for entry in &entries {
+                let css = entry.render(design, &res.css);
+                let path = match &entry.output {
+                    OutputChannel::FileSystem(path) => {
+                        write_output(&css, &entry.output)?;
+                        Some(path)
+                    }
+                    OutputChannel::Stdout => None,
+                };
+                outputs.push(json!({ "path": path, "css": css }));
+            }
+            let res =
+                json!({ "outputs": outputs, "diagnostics": diagnostics.take(), "stats": stats });
+            println!("{res}");
         }
-        Some(SubCommand::Debug(cmd)) => match app.processor.design.generate(&cmd.input) {
-            Some(r) => {
-                if cmd.print_ast {
-                    println!("{:#?}", r.rule);
-                }
-                println!("Generated {}:\n", cmd.input.green());
-                println!("{}", &r.rule.to_css_string());
-                if let Some(extra) = &r.extra_css {
-                    println!("{}", &extra.to_css_string());
-                }
+        None if opts.stats => {
+            let (res, stats) = generate_with_stats(&mut app, content, 10);
+            for entry in &entries {
+                entry.write(&app.processor.design, &res.css)?;
             }
-            None => {
-                eprintln!("Not a valid utility: {}", cmd.input.red());
+            eprint!("{}", stats.render(OutputFormat::Text));
+        }
+        None => {
+            let files = content.read(&app);
+            let res = app.generate_files(&files);
+            for entry in &entries {
+                entry.write(&app.processor.design, &res.css)?;
             }
-        },
-        Some(SubCommand::Init(_)) => {
-            write_output("{}", &OutputChannel::FileSystem("rswind.config.json".into()));
         }
+        Some(SubCommand::Debug(cmd)) => debug(&app.processor.design, cmd, opts.format, diagnostics),
+        Some(SubCommand::Analyze(cmd)) => {
+            let (_, stats) = generate_with_stats(&mut app, content, cmd.top);
+            println!("{}", stats.render(opts.format));
+        }
+        Some(SubCommand::Init(_)) => unreachable!("`init` runs before the app is built"),
     };
 
     Ok(())
 }
+
+fn debug(
+    design: &DesignSystem,
+    cmd: &DebugCommand,
+    format: OutputFormat,
+    diagnostics: &DiagnosticCollector,
+) {
+    let Some(r) = design.generate(&cmd.input) else {
+        match format.is_json() {
+            false => eprintln!("Not a valid utility: {}", cmd.input.red()),
+            true => {
+                let res = json!({ "utility": cmd.input, "valid": false, "diagnostics": diagnostics.take() });
+                println!("{res}");
+            }
+        }
+        return;
+    };
+
+    match format.is_json() {
+        false => {
+            if cmd.print_ast {
+                println!("{:#?}", r.rule);
+            }
+            println!("Generated {}:\n", cmd.input.green());
+            println!("{}", &r.rule.to_css_string());
+            if let Some(extra) = &r.extra_css {
+                println!("{}", &extra.to_css_string());
+            }
+        }
+        true => {
+            let variants = design.split_candidate(&cmd.input).map(|(vs, _)| vs.to_vec());
+            let res = json!({
+                "utility": cmd.input,
+                "valid": true,
+                "rule": r.rule,
+                "css": r.rule.to_css_string(),
+                "variants": variants.unwrap_or_default(),
+                "ordering": r.ordering,
+                "extraCss": r.extra_css.map(|extra| extra.to_css_string()),
+                "diagnostics": diagnostics.take(),
+            });
+            println!("{res}");
+        }
+    }
+}
+
+/// The outputs to write, `-i` takes precedence over stylesheets in config,
+/// `-o` alone is rejected as it would be ignored
+fn build_entries(
+    opts: &Opts,
+    stylesheets: Vec<StylesheetConfig>,
+    design: &DesignSystem,
+) -> Result<Vec<Entry>, CliError> {
+    let cwd = Path::new(&opts.cwd);
+    let output = match &opts.output {
+        Some(OutputChannel::FileSystem(path)) => OutputChannel::FileSystem(cwd.join(path)),
+        Some(OutputChannel::Stdout) | None => OutputChannel::Stdout,
+    };
+
+    let entries = match &opts.input {
+        Some(input) => vec![Entry::with_input(cwd.join(input), output, design)?],
+        None if stylesheets.is_empty() => vec![Entry::new(output)],
+        None if opts.output.is_some() => return Err(CliError::OutputWithStylesheets),
+        None => stylesheets
+            .into_iter()
+            .map(|s| {
+                let output = OutputChannel::FileSystem(cwd.join(s.output));
+                Entry::with_input(cwd.join(s.input), output, design)
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    Ok(entries)
+}
+
+/// Load the config and build the app, returns the config files and the stylesheets in config
+fn build_app(opts: &Opts) -> Result<(Generator, Vec<PathBuf>, Vec<StylesheetConfig>), CliError> {
+    let config_file = match &opts.config {
+        Some(path) => Some(ConfigFile::load(path)?),
+        None => ConfigFile::discover(&opts.cwd)?,
+    };
+    let mut config =
+        config_file.as_ref().map(ConfigFile::to_config).transpose()?.unwrap_or_default();
+    let stylesheets = mem::take(&mut config.stylesheets);
+
+    // paths and globs on the command line replace `content`
+    let content = opts
+        .content
+        .iter()
+        .filter(|c| *c != STDIN)
+        .map(|c| escape_file(c, Path::new(&opts.cwd)).into_owned())
+        .collect::<Vec<_>>();
+    if !content.is_empty() {
+        config.content = content;
+    }
+
+    let app = GeneratorProcessor::builder()
+        .with_theme(tailwind_theme)
+        .with_preset(tailwind_preset)
+        .with_plugin_factories(builtin_plugins())
+        .with_config(config)
+        .with_watch(opts.watch)
+        .with_base(Some(opts.cwd.clone()))
+        .build()?;
+
+    Ok((app, config_file.map(|file| file.sources).unwrap_or_default(), stylesheets))
+}
@@ -0,0 +1,127 @@
+use std::{
+    fmt::Debug,
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use clap::ValueEnum;
+use rswind::{
+    config::{validate::ConfigDiagnostic, GeneratorConfigError},
+    generator::AppBuildError,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, Layer};
+
+use crate::{init::InitError, CliError};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text, or a table for reports
+    #[default]
+    #[value(alias = "table")]
+    Text,
+    /// A single JSON object on stdout
+    Json,
+}
+
+impl OutputFormat {
+    pub fn is_json(&self) -> bool {
+        matches!(self, Self::Json)
+    }
+}
+
+/// A warning or error logged while running a command
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub level: String,
+    pub message: String,
+}
+
+/// Collects warnings and errors logged through `tracing`, so they can be reported in JSON
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticCollector(Arc<Mutex<Vec<Diagnostic>>>);
+
+impl DiagnosticCollector {
+    pub fn take(&self) -> Vec<Diagnostic> {
+        mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl<S: Subscriber> Layer<S> for DiagnosticCollector {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let mut visitor = MessageVisitor(String::new());
+        event.record(&mut visitor);
+        self.0.lock().unwrap().push(Diagnostic {
+            level: event.metadata().level().as_str().to_lowercase(),
+            message: visitor.0,
+        });
+    }
+}
+
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            value.clone_into(&mut self.0);
+        }
+    }
+}
+
+impl CliError {
+    /// A stable code of the error kind, reported with `--format json`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Build(AppBuildError::ConfigError(e)) | Self::Config(e) => match e {
+                GeneratorConfigError::Invalid(_) => "config_invalid",
+                GeneratorConfigError::Io(..) => "config_io",
+                GeneratorConfigError::Parse(..)
+                | GeneratorConfigError::ConfigError(_)
+                | GeneratorConfigError::DeserializeError(_) => "config_parse",
+                GeneratorConfigError::UnsupportedFormat(_) => "config_format",
+                GeneratorConfigError::MissingPackageKey(_) => "config_package_key",
+                GeneratorConfigError::InvalidExtends(_)
+                | GeneratorConfigError::CircularExtends(_) => "config_extends",
+            },
+            Self::Build(AppBuildError::GlobError(_)) => "glob",
+            Self::Build(AppBuildError::UtilityParingError(_)) => "utility",
+            Self::Build(AppBuildError::IoError(_)) => "io",
+            Self::Build(AppBuildError::PluginError(_)) => "plugin",
+            Self::Build(AppBuildError::StylesheetError(_)) => "stylesheet",
+            Self::Init(InitError::AlreadyExists(_)) => "init_exists",
+            Self::Init(InitError::Io(..)) | Self::Io(_) | Self::Stdin(_) => "io",
+            Self::WatchStdin | Self::OutputWithStylesheets => "usage",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut error = json!({ "code": self.code(), "message": self.to_string() });
+        if let Self::Build(AppBuildError::ConfigError(GeneratorConfigError::Invalid(diagnostics)))
+        | Self::Config(GeneratorConfigError::Invalid(diagnostics)) = self
+        {
+            error["diagnostics"] = diagnostics.iter().map(config_diagnostic).collect();
+        }
+        json!({ "error": error })
+    }
+}
+
+fn config_diagnostic(diagnostic: &ConfigDiagnostic) -> Value {
+    json!({
+        "pointer": diagnostic.pointer,
+        "message": diagnostic.message,
+        "file": diagnostic.file,
+        "line": diagnostic.position.map(|(line, _)| line),
+        "column": diagnostic.position.map(|(_, column)| column),
+    })
+}
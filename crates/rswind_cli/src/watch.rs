@@ -1,32 +1,74 @@
-use std::{sync::mpsc, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
 
 use notify::{EventKind, RecursiveMode, Watcher};
 use notify_debouncer_full::new_debouncer;
 use rayon::prelude::*;
 use rswind::{
-    generator::Generator,
+    design::DesignSystem,
+    generator::{Generator, GeneratorInput},
     glob::GlobFilter,
-    io::{write_output, FileInput, OutputChannel},
+    io::read_content,
     processor::ParGenerateWith,
 };
 use rswind_extractor::ParCollectExtracted;
 use rustc_hash::FxHashSet;
-use tracing::debug;
+use tracing::{debug, error, info, warn};
+
+use crate::{entry::Entry, CliError};
+
+/// A rebuilt app, with its config files and the entries of its stylesheets
+pub type Rebuilt = (Generator, Vec<PathBuf>, Vec<Entry>);
 
 pub trait WatchApp {
-    fn watch(&mut self, output: &OutputChannel);
+    /// Generate again when content files or input stylesheets change,
+    /// and build the app and entries again with `rebuild` when one of `config_files` changes
+    fn watch<F>(&mut self, entries: Vec<Entry>, config_files: Vec<PathBuf>, rebuild: F)
+    where
+        F: Fn() -> Result<Rebuilt, CliError>;
+}
+
+fn write(entry: &Entry, design: &DesignSystem, css: &str) {
+    if let Err(e) = entry.write(design, css) {
+        error!("{}", e);
+    }
+}
+
+fn watch_path(watcher: &mut impl Watcher, path: &Path, mode: RecursiveMode) {
+    if let Err(e) = watcher.watch(path, mode) {
+        warn!("Failed to watch {}: {}", path.display(), e);
+    }
+}
+
+/// Paths watched non-recursively, input stylesheets and config files
+fn watched_files<'a>(entries: &'a [Entry], config_files: &'a [PathBuf]) -> Vec<&'a PathBuf> {
+    entries.iter().filter_map(|e| e.input.as_ref()).chain(config_files).collect()
 }
 
 impl WatchApp for Generator {
-    fn watch(&mut self, output: &OutputChannel) {
+    fn watch<F>(&mut self, mut entries: Vec<Entry>, mut config_files: Vec<PathBuf>, rebuild: F)
+    where
+        F: Fn() -> Result<Rebuilt, CliError>,
+    {
         let (tx, rx) = mpsc::channel();
 
         let mut debouncer = new_debouncer(Duration::from_millis(10), None, tx).unwrap();
 
-        debouncer.watcher().watch(self.glob.base(), RecursiveMode::Recursive).unwrap();
+        for root in self.glob.roots() {
+            watch_path(debouncer.watcher(), root, RecursiveMode::Recursive);
+        }
 
-        let res = self.generate_contents();
-        write_output(&res.css, output);
+        for input in watched_files(&entries, &config_files) {
+            watch_path(debouncer.watcher(), input, RecursiveMode::NonRecursive);
+        }
+
+        let mut res = self.generate_contents();
+        for entry in entries.iter() {
+            write(entry, &self.processor.design, &res.css);
+        }
 
         for change in rx {
             let Ok(changes) = change else {
@@ -40,24 +82,75 @@ impl WatchApp for Generator {
                     _ => None,
                 })
                 .flatten()
-                .glob_filter(&self.glob)
                 .collect::<FxHashSet<_>>();
 
+            // a new config changes the design system and the stylesheets, so everything is
+            // generated again. An invalid config keeps the last app, other changes still apply
+            if changes.iter().any(|path| config_files.contains(path)) {
+                match rebuild() {
+                    Ok((app, files, new_entries)) => {
+                        let watched =
+                            self.glob.roots().iter().chain(watched_files(&entries, &config_files));
+                        for path in watched {
+                            let _ = debouncer.watcher().unwatch(path);
+                        }
+                        *self = app;
+                        config_files = files;
+                        entries = new_entries;
+                        for root in self.glob.roots() {
+                            watch_path(debouncer.watcher(), root, RecursiveMode::Recursive);
+                        }
+                        for file in watched_files(&entries, &config_files) {
+                            watch_path(debouncer.watcher(), file, RecursiveMode::NonRecursive);
+                        }
+
+                        res = self.generate_contents();
+                        for entry in entries.iter() {
+                            write(entry, &self.processor.design, &res.css);
+                        }
+
+                        info!("Config reloaded");
+                        continue;
+                    }
+                    Err(e) => error!("Failed to reload config: {}", e),
+                }
+            }
+
+            // input stylesheets are written again even if no utilities changed
+            let mut reloaded = Vec::new();
+            for (i, entry) in entries.iter_mut().enumerate() {
+                if entry.input.as_ref().is_some_and(|input| changes.contains(input)) {
+                    match entry.reload(&self.processor.design) {
+                        Ok(()) => reloaded.push(i),
+                        Err(e) => error!("{}", e),
+                    }
+                }
+            }
+
+            let changes = changes.into_iter().glob_filter(&self.glob).collect::<FxHashSet<_>>();
+
             if changes.is_empty() {
+                for i in reloaded {
+                    write(&entries[i], &self.processor.design, &res.css);
+                }
                 continue;
             }
 
             debug!("Changes: {:?}", changes);
 
-            let res = changes
+            let (extractors, extract) = (&self.extractors, &self.extract);
+            res = changes
                 .into_par_iter()
-                .map(FileInput::from_file)
+                .filter_map(read_content)
                 .collect::<Vec<_>>()
                 .par_iter()
+                .map(|f| extractors.extractor(GeneratorInput::from(f)).with_options(extract))
                 .collect_extracted()
                 .par_generate_with(&mut self.processor);
 
-            write_output(&res.css, output);
+            for entry in entries.iter() {
+                write(entry, &self.processor.design, &res.css);
+            }
         }
     }
 }
@@ -9,7 +9,7 @@ use std::{
 use assert_cmd::{cargo::CommandCargoExt, Command};
 use assert_fs::{
     assert::PathAssert,
-    fixture::{FileWriteStr, PathChild, PathCopy},
+    fixture::{FileWriteBin, FileWriteStr, PathChild, PathCopy},
     TempDir,
 };
 
@@ -17,6 +17,16 @@ fn cli() -> Command {
     Command::cargo_bin("rswind_cli").expect("Failed to build rswind_cli")
 }
 
+/// Kills a watching child process, also when a test fails
+struct KillOnDrop(process::Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
 macro_rules! until_updated {
     ($left:expr, $right:expr) => {
         let start = Instant::now();
@@ -55,7 +65,7 @@ fn test_cli_with_watch() {
     cwd.copy_from("tests", &["**/*.html"]).expect("Failed to copy fixtures");
 
     let path = cwd.path().to_owned();
-    process::Command::cargo_bin("rswind_cli")
+    let _child = process::Command::cargo_bin("rswind_cli")
         .expect("Failed to build rswind_cli")
         .arg("--watch")
         .arg("--cwd")
@@ -65,6 +75,7 @@ fn test_cli_with_watch() {
         .env("RSWIND_LOG", "debug")
         .stdout(Stdio::inherit())
         .spawn()
+        .map(KillOnDrop)
         .unwrap();
 
     until_updated!(
@@ -87,3 +98,358 @@ fn test_cli_with_watch() {
         Some(".flex{display:flex;}.text-sm{font-size:0.875rem;line-height:1.25rem;}")
     );
 }
+
+#[test]
+fn test_init() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("package.json")
+        .write_str(r#"{ "devDependencies": { "vite": "^5.0.0", "vue": "^3.4.0" } }"#)
+        .expect("Failed to write package.json");
+
+    cli().arg("init").arg("--css").arg("--cwd").arg(cwd.path()).assert().success();
+
+    cwd.child("rswind.config.json").assert(
+        r#"{
+  "$schema": "https://unpkg.com/@rswind/cli/schema.json",
+  "content": [
+    "./index.html",
+    "./src/**/*.{vue,js,jsx,ts,tsx}"
+  ],
+  "stylesheets": [
+    {
+      "input": "src/input.css",
+      "output": "dist/input.css"
+    }
+  ]
+}
+"#,
+    );
+    cwd.child("src/input.css")
+        .assert("@tailwind base;\n@tailwind components;\n@tailwind utilities;\n");
+
+    // existing files are only overwritten with `--force`
+    cli().arg("init").arg("--cwd").arg(cwd.path()).assert().failure();
+    cli().arg("init").arg("--force").arg("--cwd").arg(cwd.path()).assert().success();
+}
+
+#[test]
+fn test_init_toml() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("Cargo.toml")
+        .write_str("[package]\nname = \"app\"\n\n[dependencies]\nleptos = { version = \"0.6\" }\n")
+        .expect("Failed to write Cargo.toml");
+
+    cli().args(["init", "--config-format", "toml"]).arg("--cwd").arg(cwd.path()).assert().success();
+
+    cwd.child("rswind.config.toml").assert(
+        "#:schema https://unpkg.com/@rswind/cli/schema.json\n\ncontent = [\n  \"./index.html\",\n  \"./src/**/*.rs\",\n]\n",
+    );
+}
+
+#[test]
+fn test_cli_discover_config() {
+    let root = TempDir::new().expect("Failed to create tempdir");
+    root.child(".git/HEAD").write_str("").expect("Failed to write .git");
+    root.child("rswind.config.yaml")
+        .write_str("content:\n  - ./*.txt\n")
+        .expect("Failed to write config");
+    root.child("app/index.txt").write_str("flex").expect("Failed to write content");
+    root.child("app/index.html")
+        .write_str("<div class=\"block\"></div>")
+        .expect("Failed to write content");
+
+    cli().arg("--cwd").arg(root.child("app").path()).arg("-o").arg("index.css").assert().success();
+
+    root.child("app/index.css").assert(".flex {\n  display: flex;\n}\n");
+}
+
+#[test]
+fn test_cli_watch_config() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("index.html").write_str("<div class=\"text-primary\"></div>").unwrap();
+    let config = cwd.child("rswind.config.json");
+    config.write_str(r##"{ "theme": { "colors": { "primary": "#000" } } }"##).unwrap();
+
+    let _child = process::Command::cargo_bin("rswind_cli")
+        .expect("Failed to build rswind_cli")
+        .arg("--watch")
+        .arg("--cwd")
+        .arg(cwd.path())
+        .arg("-o")
+        .arg("index.css")
+        .spawn()
+        .map(KillOnDrop)
+        .unwrap();
+
+    let read_css = || {
+        read_to_string(cwd.child("index.css").path())
+            .map(|s| s.split_whitespace().collect::<String>())
+            .ok()
+    };
+
+    until_updated!(read_css().as_deref(), Some(".text-primary{color:#000;}"));
+
+    config.write_str(r##"{ "theme": { "colors": { "primary": "#fff" } } }"##).unwrap();
+    until_updated!(read_css().as_deref(), Some(".text-primary{color:#fff;}"));
+
+    // an invalid config keeps the last output
+    config.write_str(r#"{ "darkMode": 1 }"#).unwrap();
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!(read_css().as_deref(), Some(".text-primary{color:#fff;}"));
+
+    // content changes are generated with the last valid config
+    config.write_str(r#"{ "darkMode": 2 }"#).unwrap();
+    cwd.child("index.html").write_str("<div class=\"bg-primary\"></div>").unwrap();
+    until_updated!(
+        read_css().as_deref(),
+        Some(".bg-primary{background-color:#fff;}.text-primary{color:#fff;}")
+    );
+}
+
+#[test]
+fn test_cli_watch_stylesheets() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("index.html").write_str("<div class=\"flex\"></div>").unwrap();
+    cwd.child("a.css").write_str("@tailwind utilities;").unwrap();
+    let config = cwd.child("rswind.config.json");
+    let write_config = |output: &str| {
+        let stylesheets = format!(r#"[{{ "input": "a.css", "output": "{output}" }}]"#);
+        config.write_str(&format!(r#"{{ "stylesheets": {stylesheets} }}"#)).unwrap();
+    };
+    write_config("a.out.css");
+
+    let _child = process::Command::cargo_bin("rswind_cli")
+        .expect("Failed to build rswind_cli")
+        .arg("--watch")
+        .arg("--cwd")
+        .arg(cwd.path())
+        .spawn()
+        .map(KillOnDrop)
+        .unwrap();
+
+    let read_css = |name: &str| {
+        read_to_string(cwd.child(name).path())
+            .map(|s| s.split_whitespace().collect::<String>())
+            .ok()
+    };
+    until_updated!(read_css("a.out.css").as_deref(), Some(".flex{display:flex;}"));
+
+    // stylesheets of the reloaded config are written
+    write_config("b.out.css");
+    until_updated!(read_css("b.out.css").as_deref(), Some(".flex{display:flex;}"));
+}
+
+#[test]
+fn test_analyze() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("a.html").write_str("<div class=\"flex md:flex hover:p-4 nope\"></div>").unwrap();
+    cwd.child("b.html").write_str("<div class=\"md:p-2\"></div>").unwrap();
+    cwd.child("rswind.config.json")
+        .write_str(r#"{ "extract": { "classFunctions": {} } }"#)
+        .unwrap();
+    cwd.child("c.jsx")
+        .write_str("const a = <div className=\"flex\" />; const b = clsx('p-4', 'm-2 nope')")
+        .unwrap();
+
+    let output = cli()
+        .arg("analyze")
+        .arg("--format")
+        .arg("json")
+        .arg("--cwd")
+        .arg(cwd.path())
+        .output()
+        .expect("Failed to run analyze");
+    assert!(output.status.success());
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["files"], 3);
+    assert_eq!(stats["candidates"], 7);
+    assert_eq!(stats["valid"], 6);
+    assert_eq!(stats["invalid"], 1);
+    assert_eq!(stats["utilities"][0]["key"], "p");
+    assert_eq!(stats["utilities"][0]["count"], 3);
+    assert_eq!(stats["variants"][0], serde_json::json!({ "name": "md", "count": 2 }));
+    assert_eq!(stats["filesByClasses"][0], serde_json::json!({ "name": "a.html", "count": 3 }));
+    assert_eq!(stats["origins"][0], serde_json::json!({ "name": "clsx()", "count": 2 }));
+    assert_eq!(stats["origins"][1], serde_json::json!({ "name": "className", "count": 1 }));
+    for phase in ["walk", "extract", "generate", "serialize"] {
+        assert!(stats["timings"][phase].is_f64());
+    }
+}
+
+#[test]
+fn test_cli_stats() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("index.html").write_str("<div class=\"flex\"></div>").unwrap();
+
+    let output = cli()
+        .arg("--stats")
+        .arg("--cwd")
+        .arg(cwd.path())
+        .arg("-o")
+        .arg("index.css")
+        .output()
+        .expect("Failed to run rswind");
+    assert!(output.status.success());
+
+    let stats = String::from_utf8(output.stderr).unwrap();
+    assert!(stats.contains("Files scanned"), "{stats}");
+    assert!(stats.contains("Timings"), "{stats}");
+    cwd.child("index.css").assert(".flex {\n  display: flex;\n}\n");
+}
+
+fn run_json(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.output().expect("Failed to run rswind");
+    serde_json::from_slice(&output.stdout).expect("Failed to parse JSON output")
+}
+
+#[test]
+fn test_debug_json() {
+    let res = run_json(cli().args(["debug", "md:hover:px-4", "--format", "json"]));
+    assert_eq!(res["valid"], true);
+    assert_eq!(res["variants"], serde_json::json!(["md", "hover"]));
+    assert_eq!(res["ordering"], "padding-axis");
+    assert_eq!(res["rule"][0]["selector"], "@media (width >= 768px)");
+    assert_eq!(
+        res["rule"][0]["rules"][0]["decls"][0],
+        serde_json::json!({ "name": "padding-left", "value": "1rem" })
+    );
+    assert_eq!(res["extraCss"], serde_json::Value::Null);
+
+    let res = run_json(cli().args(["debug", "w-[theme(spacing.nope)]", "--format", "json"]));
+    assert_eq!(res["valid"], false);
+    assert_eq!(res["diagnostics"][0]["level"], "warn");
+    // `--format` is global, it can be passed before the subcommand
+    let res = run_json(cli().args(["--format", "json", "debug", "flex"]));
+    assert_eq!(res["valid"], true);
+
+    // config formats belong to `init`
+    cli().args(["debug", "flex", "--format", "toml"]).assert().failure();
+    cli().args(["init", "--format", "toml"]).assert().failure();
+}
+
+#[test]
+fn test_cli_json() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("index.html").write_str("<div class=\"flex nope\"></div>").unwrap();
+
+    let res =
+        run_json(cli().arg("--cwd").arg(cwd.path()).args(["-o", "index.css", "--format", "json"]));
+    assert_eq!(res["outputs"][0]["css"], ".flex {\n  display: flex;\n}\n");
+    assert_eq!(res["diagnostics"], serde_json::json!([]));
+    assert_eq!(res["stats"]["valid"], 1);
+    assert_eq!(res["stats"]["invalid"], 1);
+    cwd.child("index.css").assert(".flex {\n  display: flex;\n}\n");
+
+    cwd.child("rswind.config.json").write_str("{\n  \"darkMode\": 1\n}").unwrap();
+    let output = cli()
+        .arg("--cwd")
+        .arg(cwd.path())
+        .args(["--format", "json"])
+        .output()
+        .expect("Failed to run rswind");
+    assert!(!output.status.success());
+    let res: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(res["error"]["code"], "config_invalid");
+    assert_eq!(res["error"]["diagnostics"][0]["pointer"], "/darkMode");
+    assert_eq!(res["error"]["diagnostics"][0]["line"], 2);
+}
+
+#[test]
+fn test_cli_stdin() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("index.html").write_str("<div class=\"flex\"></div>").unwrap();
+
+    cli()
+        .arg("--cwd")
+        .arg(cwd.path())
+        .arg("-")
+        .write_stdin("<div class=\"italic\"></div>")
+        .assert()
+        .success()
+        .stdout(".italic {\n  font-style: italic;\n}\n");
+
+    cli()
+        .arg("--cwd")
+        .arg(cwd.path())
+        .args(["-", "--stdin-kind", "ecma", "index.html"])
+        .write_stdin("const a = 'underline';")
+        .assert()
+        .success()
+        .stdout(".flex {\n  display: flex;\n}\n.underline {\n  text-decoration: underline;\n}\n");
+
+    assert!(!cwd.child("stdout").path().exists());
+}
+
+#[test]
+fn test_cli_content_paths() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("rswind.config.json").write_str(r#"{ "content": ["./src/**/*.html"] }"#).unwrap();
+    cwd.child("src/index.html").write_str("<div class=\"flex\"></div>").unwrap();
+    cwd.child("app/[id]/page.tsx").write_str("<div className=\"italic\" />").unwrap();
+    cwd.child("app/i/page.tsx").write_str("<div className=\"block\" />").unwrap();
+
+    cli()
+        .arg("--cwd")
+        .arg(cwd.path())
+        .arg("app/[id]/page.tsx")
+        .assert()
+        .success()
+        .stdout(".italic {\n  font-style: italic;\n}\n");
+}
+
+#[test]
+fn test_cli_output_with_stylesheets() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("index.html").write_str("<div class=\"flex\"></div>").unwrap();
+    cwd.child("a.css").write_str("@tailwind utilities;").unwrap();
+    cwd.child("rswind.config.json")
+        .write_str(r#"{ "stylesheets": [{ "input": "a.css", "output": "a.out.css" }] }"#)
+        .unwrap();
+
+    // `-o` alone would be ignored in favor of `stylesheets`
+    let res =
+        run_json(cli().arg("--cwd").arg(cwd.path()).args(["-o", "b.css", "--format", "json"]));
+    assert_eq!(res["error"]["code"], "usage");
+    assert!(!cwd.child("a.out.css").path().exists());
+
+    cli().arg("--cwd").arg(cwd.path()).args(["-i", "a.css", "-o", "b.css"]).assert().success();
+    cwd.child("b.css").assert(".flex {\n  display: flex;\n}\n");
+
+    cli().arg("--cwd").arg(cwd.path()).assert().success();
+    cwd.child("a.out.css").assert(".flex {\n  display: flex;\n}\n");
+}
+
+#[test]
+fn test_cli_skip_unreadable() {
+    let cwd = TempDir::new().expect("Failed to create tempdir");
+    cwd.child("index.html").write_str("<div class=\"flex\"></div>").unwrap();
+    cwd.child("binary.js").write_binary(&[0xff, 0xfe, b'f', b'l']).unwrap();
+
+    cli().arg("--cwd").arg(cwd.path()).args(["-o", "dist/css/index.css"]).assert().success();
+
+    cwd.child("dist/css/index.css").assert(".flex {\n  display: flex;\n}\n");
+}
+
+#[test]
+fn test_cli_watch_stdin() {
+    // stdin is kept open, the error is reported without waiting for it
+    let mut child = process::Command::cargo_bin("rswind_cli")
+        .expect("Failed to build rswind_cli")
+        .args(["--watch", "-"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(KillOnDrop)
+        .unwrap();
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.0.try_wait().unwrap() {
+            break status;
+        }
+        assert!(start.elapsed() < Duration::from_secs(10), "rswind is waiting for stdin");
+        thread::sleep(Duration::from_millis(100));
+    };
+    assert!(!status.success());
+}
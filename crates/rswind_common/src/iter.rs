@@ -1,6 +1,7 @@
 use either::Either::{self, Left, Right};
 use rayon::prelude::*;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IntoIterKind {
     Sequential,
     Parallel,
@@ -36,8 +36,9 @@ impl instance_code::InstanceCode for UtilityHandler {
         use instance_code::quote;
         let color = crate::common::as_color("$0", Some("1"));
 
-        let decls = self.0(MetaData::default(), SmolStr::new("$0")).decls;
-        let with_modifier = self.0(MetaData::modifier("1"), SmolStr::new("$0")).decls;
+        let decls = self.call(MetaData::default(), SmolStr::new("$0")).unwrap_or_default().decls;
+        let with_modifier =
+            self.call(MetaData::modifier("1"), SmolStr::new("$0")).unwrap_or_default().decls;
 
         let value_count = decls.iter().filter(|d| d.value.contains("$0")).count();
 
@@ -67,13 +68,11 @@ impl instance_code::InstanceCode for UtilityHandler {
             })
             .collect::<Vec<_>>();
         quote! {
-            rswind_core::process::UtilityHandler(
-                Box::new(|_meta, value| {
-                    rswind_core::css::css! {
-                        #(#decls)*
-                    }
-                })
-            )
+            rswind_core::process::UtilityHandler::new(|_meta, value| {
+                rswind_core::css::css! {
+                    #(#decls)*
+                }
+            })
         }
     }
 }
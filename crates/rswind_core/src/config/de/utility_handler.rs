@@ -1,11 +1,11 @@
 use std::fmt::{self, Write};
 
-use derive_more::{Constructor, Deref, DerefMut};
+use derive_more::{Deref, DerefMut};
 use rswind_css::{Decl, Rule};
 use rswind_extractor::cursor::Cursor;
 use rustc_hash::FxHashMap;
 use serde::{
-    de::{MapAccess, Visitor},
+    de::{self, MapAccess, Visitor},
     Deserialize, Deserializer,
 };
 use smol_str::SmolStr;
@@ -17,45 +17,126 @@ impl<'de> Deserialize<'de> for UtilityHandler {
     where
         D: Deserializer<'de>,
     {
-        struct UtilityHandlerVisitor;
+        let template = RuleTemplate::deserialize(deserializer)?;
 
-        impl<'de> Visitor<'de> for UtilityHandlerVisitor {
-            type Value = UtilityHandler;
+        Ok(UtilityHandler::new(move |meta, value| {
+            let raw = meta.raw_value.as_deref();
+            template.render("&", &RenderData::new(&value, meta.modifier.as_deref()).with_raw(raw))
+        }))
+    }
+}
+
+/// A rule of templates, nested rules are written as objects
+///
+/// e.g. `{ "color": "$0", "&::placeholder": { "opacity": "${1:-1}" } }`
+#[derive(Debug, Default, PartialEq)]
+struct RuleTemplate {
+    decls: FxHashMap<SmolStr, Template>,
+    rules: Vec<(SmolStr, RuleTemplate)>,
+}
+
+impl RuleTemplate {
+    fn render(&self, selector: &str, data: &RenderData) -> Rule {
+        let decls = self.decls.iter().map(|(k, tpl)| {
+            let mut w = smol_str::Writer::new();
+            let _ = tpl.render(&mut w, data);
+
+            Decl::new(k.as_str(), SmolStr::from(w))
+        });
+
+        let mut rule = Rule::new_with_decls(selector, decls.collect());
+        rule.rules.0.extend(self.rules.iter().map(|(selector, rule)| rule.render(selector, data)));
+        rule
+    }
+}
+
+struct RuleTemplateVisitor;
+
+impl<'de> Visitor<'de> for RuleTemplateVisitor {
+    type Value = RuleTemplate;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of utility handler")
+    }
 
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("a map of utility handler")
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut rule = RuleTemplate::default();
+
+        while let Some(key) = map.next_key::<SmolStr>()? {
+            match map.next_value::<TemplateValue>()? {
+                TemplateValue::Decl(tpl) => {
+                    rule.decls.insert(key, tpl);
+                }
+                TemplateValue::Rule(nested) => rule.rules.push((key, nested)),
+            }
+        }
+
+        Ok(rule)
+    }
+}
+
+impl<'de> Deserialize<'de> for RuleTemplate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(RuleTemplateVisitor)
+    }
+}
+
+/// The value of a [`RuleTemplate`] entry
+enum TemplateValue {
+    Decl(Template),
+    Rule(RuleTemplate),
+}
+
+impl<'de> Deserialize<'de> for TemplateValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TemplateValueVisitor;
+
+        impl<'de> Visitor<'de> for TemplateValueVisitor {
+            type Value = TemplateValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a template string or a map of nested rule")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                TemplateParser::new(v).parse().map(TemplateValue::Decl).map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
             }
 
             fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
             where
                 A: MapAccess<'de>,
             {
-                let mut map = map;
-                let mut handlers = FxHashMap::default();
-
-                while let Some((key, value)) = map.next_entry::<SmolStr, SmolStr>()? {
-                    handlers.insert(key, TemplateParser::new(&value).parse());
-                }
-
-                Ok(UtilityHandler::new(move |meta, value| {
-                    Rule::new(handlers.iter().map(|(k, tpl)| {
-                        let mut w = smol_str::Writer::new();
-                        let _ =
-                            tpl.render(&mut w, &RenderData::new(&value, meta.modifier.as_deref()));
-
-                        Decl::new(k.as_str(), SmolStr::from(w))
-                    }))
-                }))
+                RuleTemplateVisitor.visit_map(map).map(TemplateValue::Rule)
             }
         }
 
-        deserializer.deserialize_map(UtilityHandlerVisitor)
+        deserializer.deserialize_any(TemplateValueVisitor)
     }
 }
 
 /// A simple template for utility handler
 #[derive(Debug, PartialEq)]
-
 struct Template {
     parts: Vec<TemplatePart>,
 }
@@ -69,26 +150,29 @@ enum TemplatePart {
     Placeholder(Placeholder),
 }
 
-#[derive(Constructor)]
 struct RenderData<'a> {
     value: &'a str,
     modifier: Option<&'a str>,
+    raw: Option<&'a str>,
+}
+
+impl<'a> RenderData<'a> {
+    fn new(value: &'a str, modifier: Option<&'a str>) -> Self {
+        Self { value, modifier, raw: None }
+    }
+
+    fn with_raw(mut self, raw: Option<&'a str>) -> Self {
+        self.raw = raw;
+        self
+    }
 }
 
 impl Template {
     pub fn render(&self, writer: &mut impl Write, data: &RenderData) -> fmt::Result {
         for part in &self.parts {
             match part {
-                TemplatePart::Literal(lit) => {
-                    writer.write_str(lit)?;
-                }
-                TemplatePart::Placeholder(Placeholder::Value(m)) => match m {
-                    Some(typ) => typ.render(writer, data)?,
-                    None => writer.write_str(data.value)?,
-                },
-                TemplatePart::Placeholder(Placeholder::Modifier) => {
-                    data.modifier.as_ref().map(|v| writer.write_str(v)).transpose()?;
-                }
+                TemplatePart::Literal(lit) => writer.write_str(lit)?,
+                TemplatePart::Placeholder(placeholder) => placeholder.render(writer, data)?,
             }
         }
 
@@ -124,12 +208,48 @@ impl ModifierType {
 enum Placeholder {
     /// The value placeholder
     ///
-    /// use $0 to represent the value
+    /// use $0 or ${0} to represent the value
     Value(Option<ModifierType>),
     /// The modifier placeholder
     ///
-    /// use $1 to represent the modifier
+    /// use $1 or ${1} to represent the modifier
     Modifier,
+    /// The value as written in the candidate, before resolving from theme
+    ///
+    /// use ${raw} to represent it, e.g. `4` in `tab-4`, `3px` in `tab-[3px]`
+    Raw,
+    /// `${1:-fallback}`, renders the fallback when the placeholder is empty
+    Fallback(Box<Placeholder>, Template),
+    /// `${1:+text}`, renders the text only when the placeholder is not empty
+    Conditional(Box<Placeholder>, Template),
+}
+
+impl Placeholder {
+    fn is_present(&self, data: &RenderData) -> bool {
+        match self {
+            Self::Value(_) => !data.value.is_empty(),
+            Self::Modifier => data.modifier.is_some_and(|m| !m.is_empty()),
+            Self::Raw => data.raw.is_some_and(|r| !r.is_empty()),
+            Self::Fallback(p, _) | Self::Conditional(p, _) => p.is_present(data),
+        }
+    }
+
+    fn render(&self, writer: &mut impl Write, data: &RenderData) -> fmt::Result {
+        match self {
+            Self::Value(Some(typ)) => typ.render(writer, data),
+            Self::Value(None) => writer.write_str(data.value),
+            Self::Modifier => data.modifier.map_or(Ok(()), |v| writer.write_str(v)),
+            Self::Raw => data.raw.map_or(Ok(()), |v| writer.write_str(v)),
+            Self::Fallback(p, fallback) => match p.is_present(data) {
+                true => p.render(writer, data),
+                false => fallback.render(writer, data),
+            },
+            Self::Conditional(p, text) => match p.is_present(data) {
+                true => text.render(writer, data),
+                false => Ok(()),
+            },
+        }
+    }
 }
 
 #[derive(Deref, DerefMut)]
@@ -151,32 +271,73 @@ impl<'a> TemplateParser<'a> {
         &self.input[start..self.pos()]
     }
 
-    fn parse(&mut self) -> Template {
+    fn parse(&mut self) -> Result<Template, String> {
+        self.parse_until(None)
+    }
+
+    /// Parse until `end`, which is consumed, or the end of input if `end` is `None`
+    fn parse_until(&mut self, end: Option<char>) -> Result<Template, String> {
         let mut parts = Vec::new();
 
-        while !self.is_eof() {
-            let lit = self.consume(|c| loop {
-                c.eat_until_char(b'$');
-                if c.is_eof() || matches!(c.second(), '0' | '1') {
-                    break;
+        loop {
+            let lit = self.consume(|c| {
+                while !c.is_eof() {
+                    match c.first() {
+                        '$' if matches!(c.second(), '0' | '1' | '{') => break,
+                        ch if Some(ch) == end => break,
+                        _ => c.bump(),
+                    };
                 }
-                c.bump();
             });
 
-            self.bump();
             if !lit.is_empty() {
                 parts.push(TemplatePart::Literal(SmolStr::from(lit)));
             }
+
             match self.bump() {
-                '0' => parts
-                    .push(TemplatePart::Placeholder(Placeholder::Value(ModifierType::parse(self)))),
-                '1' => parts.push(TemplatePart::Placeholder(Placeholder::Modifier)),
-                '\0' => return Template { parts },
-                _ => unreachable!(),
+                '\0' if end.is_some() => {
+                    return Err(format!("unclosed placeholder in template `{}`", self.input))
+                }
+                '\0' => return Ok(Template { parts }),
+                '$' => {}
+                _ => return Ok(Template { parts }),
             }
+
+            let placeholder = match self.bump() {
+                '0' => Placeholder::Value(ModifierType::parse(self)),
+                '1' => Placeholder::Modifier,
+                _ => self.parse_braced()?,
+            };
+            parts.push(TemplatePart::Placeholder(placeholder));
         }
+    }
+
+    /// Parse `${...}` after the opening brace
+    fn parse_braced(&mut self) -> Result<Placeholder, String> {
+        let name = self.consume(|c| c.eat_while(|c| c.is_ascii_alphanumeric() || c == '_'));
+        let placeholder = match name {
+            "0" => Placeholder::Value(ModifierType::parse(self)),
+            "1" => Placeholder::Modifier,
+            "raw" => Placeholder::Raw,
+            _ => {
+                return Err(format!(
+                    "unknown placeholder `${{{name}}}` in template `{}`",
+                    self.input
+                ))
+            }
+        };
+
+        let placeholder = if self.eat_str(":-") {
+            Placeholder::Fallback(Box::new(placeholder), self.parse_until(Some('}'))?)
+        } else if self.eat_str(":+") {
+            Placeholder::Conditional(Box::new(placeholder), self.parse_until(Some('}'))?)
+        } else if self.eat_str("}") {
+            placeholder
+        } else {
+            return Err(format!("expected `}}` in template `{}`", self.input));
+        };
 
-        Template { parts }
+        Ok(placeholder)
     }
 }
 
@@ -208,6 +369,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_nested_rule_deserializer() -> anyhow::Result<()> {
+        let input = json!({
+            "color": "$0",
+            "&::placeholder": {
+                "opacity": "${1:-1}"
+            }
+        });
+
+        let res = UtilityHandler::deserialize(input)?;
+
+        assert_eq!(
+            res.call(MetaData::default(), SmolStr::from("red")).to_css_minified(),
+            "&{color:red;&::placeholder{opacity:1;}}"
+        );
+        assert_eq!(
+            res.call(MetaData::modifier("0.5"), SmolStr::from("red")).to_css_minified(),
+            "&{color:red;&::placeholder{opacity:0.5;}}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        let err = UtilityHandler::deserialize(json!({ "color": "${2}" })).unwrap_err();
+        assert_eq!(err.to_string(), "unknown placeholder `${2}` in template `${2}`");
+
+        let err = UtilityHandler::deserialize(json!({ "color": "${1:-1" })).unwrap_err();
+        assert_eq!(err.to_string(), "unclosed placeholder in template `${1:-1`");
+    }
+
     #[cfg(test)]
     mod template {
         use super::super::*;
@@ -217,7 +410,7 @@ mod tests {
             let input = "color: $1;";
 
             let mut parser = TemplateParser::new(input);
-            let res = parser.parse();
+            let res = parser.parse().unwrap();
 
             assert_eq!(
                 res,
@@ -233,7 +426,7 @@ mod tests {
 
         fn run(input: &str, data: RenderData) -> String {
             let mut parser = TemplateParser::new(input);
-            let template = parser.parse();
+            let template = parser.parse().unwrap();
 
             let mut writer = String::new();
             let _ = template.render(&mut writer, &data);
@@ -264,5 +457,37 @@ mod tests {
                 "color: color-mix(in srgb, #123456 50%, transparent); opacity: 0.5;"
             );
         }
+
+        #[test]
+        fn test_template_render_braced() {
+            let data = RenderData::new("1rem", None);
+            assert_eq!(run("${0}px calc(${0} * -1)", data), "1rempx calc(1rem * -1)");
+        }
+
+        #[test]
+        fn test_template_render_fallback() {
+            assert_eq!(run("${1:-1}", RenderData::new("red", None)), "1");
+            assert_eq!(run("${1:-1}", RenderData::new("red", Some("0.5"))), "0.5");
+            assert_eq!(run("${1:-$0}", RenderData::new("red", None)), "red");
+        }
+
+        #[test]
+        fn test_template_render_conditional() {
+            let template = "$0${1:+ / $1}";
+            assert_eq!(run(template, RenderData::new("1rem", None)), "1rem");
+            assert_eq!(run(template, RenderData::new("1rem", Some("2"))), "1rem / 2");
+        }
+
+        #[test]
+        fn test_template_render_raw() {
+            let data = RenderData::new("1rem", None).with_raw(Some("4"));
+            assert_eq!(run("--raw: ${raw}; --value: $0", data), "--raw: 4; --value: 1rem");
+        }
+
+        #[test]
+        fn test_template_literal_dollar() {
+            let data = RenderData::new("1rem", None);
+            assert_eq!(run("$a {} $0}", data), "$a {} 1rem}");
+        }
     }
 }
@@ -0,0 +1,275 @@
+//! Loading config files in JSON, TOML or YAML, searching parent directories and merging `extends`
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use config::{Config, FileFormat};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::{
+    validate::{locate, validate, Source},
+    GeneratorConfig, GeneratorConfigError,
+};
+
+/// Config file names, searched in this order in each directory
+pub static CONFIG_FILES: &[&str] =
+    &["rswind.config.json", "rswind.config.toml", "rswind.config.yaml", "rswind.config.yml"];
+
+/// The key of the config in `package.json`
+static PACKAGE_KEY: &str = "rswind";
+
+/// A config file merged with the files it extends
+#[derive(Debug, Clone)]
+pub struct ConfigFile {
+    /// The config file followed by the files it extends
+    pub sources: Vec<PathBuf>,
+    pub value: Value,
+}
+
+impl ConfigFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GeneratorConfigError> {
+        let mut sources = Vec::new();
+        let value = load_value(path.as_ref(), &mut sources, &mut Vec::new())?;
+        Ok(Self { sources, value })
+    }
+
+    /// Find the config of `cwd` in it or its parent directories, see [`find_config`]
+    pub fn discover(cwd: impl AsRef<Path>) -> Result<Option<Self>, GeneratorConfigError> {
+        find_config(cwd.as_ref()).map(Self::load).transpose()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.sources[0]
+    }
+
+    /// Validate and deserialize the config, all invalid values are reported with their location
+    pub fn to_config(&self) -> Result<GeneratorConfig, GeneratorConfigError> {
+        let mut diagnostics = validate(&self.value);
+        if !diagnostics.is_empty() {
+            let sources =
+                self.sources.iter().filter_map(|path| read_source(path)).collect::<Vec<_>>();
+            for diagnostic in &mut diagnostics {
+                locate(diagnostic, &self.value, &sources);
+            }
+            return Err(GeneratorConfigError::Invalid(diagnostics));
+        }
+
+        Ok(GeneratorConfig::deserialize(&self.value)?)
+    }
+}
+
+/// Search `cwd` and its parent directories for a config file or a `package.json` with a
+/// `rswind` key, stopping at the repository root
+pub fn find_config(cwd: &Path) -> Option<PathBuf> {
+    let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_owned());
+
+    for dir in cwd.ancestors() {
+        if let Some(path) = CONFIG_FILES.iter().map(|name| dir.join(name)).find(|p| p.is_file()) {
+            return Some(path);
+        }
+
+        let package = dir.join("package.json");
+        if matches!(read_package(&package), Ok(Some(_))) {
+            return Some(package);
+        }
+
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    None
+}
+
+/// Read `path` and the files it extends, `chain` holds the files extending it
+fn load_value(
+    path: &Path,
+    sources: &mut Vec<PathBuf>,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Value, GeneratorConfigError> {
+    let path = path.canonicalize().map_err(|e| GeneratorConfigError::Io(path.to_owned(), e))?;
+    if chain.contains(&path) {
+        return Err(GeneratorConfigError::CircularExtends(path));
+    }
+
+    let mut value = read_value(&path)?;
+    let extends = match value.as_object_mut().and_then(|config| config.remove("extends")) {
+        None => Vec::new(),
+        Some(Value::String(extend)) => vec![extend],
+        Some(Value::Array(extends)) => extends
+            .into_iter()
+            .map(|extend| match extend {
+                Value::String(extend) => Ok(extend),
+                _ => Err(GeneratorConfigError::InvalidExtends(path.clone())),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err(GeneratorConfigError::InvalidExtends(path)),
+    };
+
+    sources.push(path.clone());
+    chain.push(path.clone());
+
+    // extended files are relative to the file extending them
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut merged = Value::Object(Map::new());
+    for extend in extends {
+        merge(&mut merged, load_value(&dir.join(extend), sources, chain)?, 0);
+    }
+    merge(&mut merged, value, 0);
+
+    chain.pop();
+    Ok(merged)
+}
+
+fn read_value(path: &Path) -> Result<Value, GeneratorConfigError> {
+    if path.file_name().is_some_and(|name| name == "package.json") {
+        return read_package(path)?
+            .ok_or_else(|| GeneratorConfigError::MissingPackageKey(path.to_owned()));
+    }
+
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => return read_json(path),
+        Some("toml") => FileFormat::Toml,
+        Some("yaml" | "yml") => FileFormat::Yaml,
+        _ => return Err(GeneratorConfigError::UnsupportedFormat(path.to_owned())),
+    };
+
+    Ok(Config::builder()
+        .add_source(config::File::from(path).format(format))
+        .build()?
+        .try_deserialize::<Value>()?)
+}
+
+fn read_source(path: &Path) -> Option<Source> {
+    let json = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => {
+            let is_package = path.file_name().is_some_and(|name| name == "package.json");
+            Some((fs::read_to_string(path).ok()?, if is_package { "/rswind" } else { "" }))
+        }
+        _ => None,
+    };
+    Some(Source { path: path.to_owned(), value: read_value(path).ok()?, json })
+}
+
+fn read_json(path: &Path) -> Result<Value, GeneratorConfigError> {
+    let content =
+        fs::read_to_string(path).map_err(|e| GeneratorConfigError::Io(path.to_owned(), e))?;
+    serde_json::from_str(&content).map_err(|e| GeneratorConfigError::Parse(path.to_owned(), e))
+}
+
+/// The `rswind` key of a `package.json`
+fn read_package(path: &Path) -> Result<Option<Value>, GeneratorConfigError> {
+    Ok(match read_json(path)? {
+        Value::Object(mut package) => package.remove(PACKAGE_KEY),
+        _ => None,
+    })
+}
+
+/// Merge `value` into `base`, objects like `theme` are merged recursively,
+/// top level arrays like `content` and `utilities` are concatenated, other values are replaced
+fn merge(base: &mut Value, value: Value, depth: usize) {
+    match (base, value) {
+        (Value::Object(base), Value::Object(value)) => {
+            for (key, value) in value {
+                match base.get_mut(&key) {
+                    Some(base) => merge(base, value, depth + 1),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(value)) if depth == 1 => {
+            for value in value {
+                if !base.contains(&value) {
+                    base.push(value);
+                }
+            }
+        }
+        (base, value) => *base = value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_extends() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "rswind.base.toml",
+            r##"
+content = ["./src/**/*.html"]
+darkMode = "selector"
+
+[theme.extend.colors]
+primary = "#3490dc"
+danger = "#e3342f"
+"##,
+        );
+        write(
+            root,
+            "packages/app/rswind.config.json",
+            r##"{
+  "extends": "../../rswind.base.toml",
+  "content": ["./src/**/*.html", "./src/**/*.vue"],
+  "theme": { "extend": { "colors": { "primary": "#ff0000" } } }
+}"##,
+        );
+
+        let file = ConfigFile::discover(root.join("packages/app/src")).unwrap().unwrap();
+        assert!(file.path().ends_with("packages/app/rswind.config.json"));
+        assert_eq!(file.sources.len(), 2);
+        assert_eq!(
+            file.value,
+            json!({
+                "content": ["./src/**/*.html", "./src/**/*.vue"],
+                "darkMode": "selector",
+                "theme": { "extend": { "colors": { "primary": "#ff0000", "danger": "#e3342f" } } }
+            })
+        );
+        assert_eq!(file.to_config().unwrap().dark_mode, "selector");
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{ "name": "root", "rswind": { "content": ["./**/*.rs"] } }"#,
+        );
+        // `package.json` without a `rswind` key is skipped
+        write(root, "crates/app/package.json", r#"{ "name": "app" }"#);
+        write(root, "crates/app/.git/HEAD", "");
+        write(root, "crates/lib/rswind.config.yaml", "content:\n  - ./src/**/*.rs\n");
+
+        assert_eq!(find_config(&root.join("crates/app")), None);
+        let file = ConfigFile::discover(root.join("crates/lib")).unwrap().unwrap();
+        assert_eq!(file.value, json!({ "content": ["./src/**/*.rs"] }));
+        let file = ConfigFile::discover(root.join("crates")).unwrap().unwrap();
+        assert_eq!(file.value, json!({ "content": ["./**/*.rs"] }));
+
+        write(root, "a.json", r#"{ "extends": "b.json" }"#);
+        write(root, "b.json", r#"{ "extends": ["a.json"] }"#);
+        assert!(matches!(
+            ConfigFile::load(root.join("a.json")),
+            Err(GeneratorConfigError::CircularExtends(_))
+        ));
+    }
+}
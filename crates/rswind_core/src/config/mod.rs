@@ -1,12 +1,19 @@
 pub mod de;
+pub mod load;
 #[cfg(feature = "json_schema")]
 pub mod schema;
+pub mod validate;
 
-use std::{io, str::FromStr};
+use std::{
+    fmt::Write,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use config::Config;
 use derive_more::{Deref, DerefMut};
 use rswind_css::DeclList;
+use rswind_extractor::{ecma::ClassFunctions, ExtractOptions, Transform};
 use rustc_hash::FxHashMap as HashMap;
 use serde::Deserialize;
 use smol_str::SmolStr;
@@ -14,6 +21,7 @@ use thiserror::Error;
 use tracing::{debug, info, instrument};
 
 use crate::{ordering::OrderingKey, parse::UtilityBuilder};
+use validate::ConfigDiagnostic;
 
 pub static DEFAULT_CONFIG_PATH: &str = "rswind.config.json";
 
@@ -47,6 +55,116 @@ pub struct Features {
     pub strict_mode: bool,
 }
 
+/// A plugin enabled in config, either a name or a name with options
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub enum PluginConfig {
+    Name(SmolStr),
+    WithOptions {
+        name: SmolStr,
+        #[serde(default)]
+        options: serde_json::Value,
+    },
+}
+
+impl PluginConfig {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::WithOptions { name, .. } => name,
+        }
+    }
+
+    pub fn options(&self) -> &serde_json::Value {
+        static NULL: serde_json::Value = serde_json::Value::Null;
+        match self {
+            Self::Name(_) => &NULL,
+            Self::WithOptions { options, .. } => options,
+        }
+    }
+}
+
+/// How candidates are extracted from source files
+#[derive(Debug, Deserialize, Default)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractConfig {
+    /// Extract only from class functions and class attributes in JavaScript and TypeScript files,
+    /// instead of every string literal, e.g. `{}` to use the default names
+    pub class_functions: Option<ClassFunctionsConfig>,
+
+    /// Extract candidates from fenced code blocks in markdown and MDX files, default to `false`
+    #[serde(default)]
+    pub code_blocks: bool,
+
+    /// Transforms applied to each candidate in order, e.g. `[{ "stripPrefix": "x-" }]`
+    #[serde(default)]
+    pub transforms: Vec<TransformConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum TransformConfig {
+    /// Strip a prefix if present, e.g. `x-` turns `x-flex` into `flex`
+    StripPrefix(String),
+    /// Strip a suffix if present
+    StripSuffix(String),
+}
+
+impl From<&TransformConfig> for Transform {
+    fn from(config: &TransformConfig) -> Self {
+        match config {
+            TransformConfig::StripPrefix(prefix) => Transform::StripPrefix(prefix.clone()),
+            TransformConfig::StripSuffix(suffix) => Transform::StripSuffix(suffix.clone()),
+        }
+    }
+}
+
+/// An input stylesheet and the path its processed CSS is written to
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct StylesheetConfig {
+    /// Input CSS file relative to the working directory, e.g. `src/app.css`
+    pub input: String,
+    /// Output CSS file relative to the working directory, e.g. `dist/app.css`
+    pub output: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ClassFunctionsConfig {
+    /// Functions and template tags, default to `["clsx", "cn", "cva", "tw"]`
+    #[serde(default = "default_class_functions")]
+    pub functions: Vec<String>,
+
+    /// JSX attributes and object properties, default to `["className", "class"]`
+    #[serde(default = "default_class_attributes")]
+    pub attributes: Vec<String>,
+}
+
+fn default_class_functions() -> Vec<String> {
+    ClassFunctions::default().functions
+}
+
+fn default_class_attributes() -> Vec<String> {
+    ClassFunctions::default().attributes
+}
+
+impl ExtractConfig {
+    pub fn to_options(&self) -> ExtractOptions {
+        ExtractOptions {
+            class_functions: self.class_functions.as_ref().map(|c| ClassFunctions {
+                functions: c.functions.clone(),
+                attributes: c.attributes.clone(),
+            }),
+            code_blocks: self.code_blocks,
+            transforms: self.transforms.iter().map(Transform::from).collect(),
+        }
+    }
+}
+
 fn default_dark_mode() -> SmolStr {
     "media".into()
 }
@@ -66,9 +184,14 @@ mod wbg_shim {
     #[serde(rename_all = "camelCase")]
     #[serde(default)]
     pub struct GeneratorConfig {
-        /// The glob pattern to match input files
+        /// The glob pattern to match input files, patterns starting with `!` are excluded
         pub content: Vec<String>,
 
+        /// Globs of files and directories to skip, relative to the base directory,
+        /// globs starting with `**` apply below every walked directory.
+        /// Files ignored by `.gitignore` or `.ignore` are always skipped.
+        pub exclude: Vec<String>,
+
         /// User define themes, will be merged with the default theme
         pub theme: ThemeConfig,
 
@@ -90,6 +213,16 @@ mod wbg_shim {
 
         /// User defined static utilities e.g. `flex`
         pub static_utilities: StaticUtilityConfig,
+
+        /// Plugins to enable, e.g. `["typography", { "name": "forms", "options": { "strategy": "class" } }]`
+        pub plugins: Vec<PluginConfig>,
+
+        /// How candidates are extracted from source files
+        pub extract: ExtractConfig,
+
+        /// Input stylesheets processed by the CLI, generated CSS is inserted at their
+        /// `@tailwind` or `@import "rswind"` markers
+        pub stylesheets: Vec<StylesheetConfig>,
     }
 }
 
@@ -97,10 +230,31 @@ pub use wbg_shim::GeneratorConfig;
 
 #[derive(Debug, Error)]
 pub enum GeneratorConfigError {
-    // #[error("Failed to deserialize configuration: {0}")]
-    // DeserializeError(#[from] serde_json::Error),
+    #[error("Failed to deserialize configuration: {0}")]
+    DeserializeError(#[from] serde_json::Error),
     #[error("Failed to read configuration file: {0}")]
     ConfigError(#[from] config::ConfigError),
+    #[error("Failed to read configuration file {0:?}: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("Failed to parse configuration file {0:?}: {1}")]
+    Parse(PathBuf, serde_json::Error),
+    #[error("Unsupported configuration file {0:?}, expected json, toml or yaml")]
+    UnsupportedFormat(PathBuf),
+    #[error("No `rswind` key in {0:?}")]
+    MissingPackageKey(PathBuf),
+    #[error("`extends` in {0:?} should be a path or a list of paths")]
+    InvalidExtends(PathBuf),
+    #[error("Circular `extends` of {0:?}")]
+    CircularExtends(PathBuf),
+    #[error("Invalid configuration:{}", format_diagnostics(.0))]
+    Invalid(Vec<ConfigDiagnostic>),
+}
+
+fn format_diagnostics(diagnostics: &[ConfigDiagnostic]) -> String {
+    diagnostics.iter().fold(String::new(), |mut res, diagnostic| {
+        let _ = write!(res, "\n  {diagnostic}");
+        res
+    })
 }
 
 #[cfg(feature = "napi")]
@@ -111,27 +265,39 @@ impl From<GeneratorConfigError> for napi::Error {
 }
 
 impl GeneratorConfig {
+    /// Load a config file, `rswind.config` tries each supported extension.
+    /// If the file is not found, use the default configuration
     #[instrument]
     pub fn from_file(name: &str) -> Result<Self, GeneratorConfigError> {
-        let config_result = Config::builder().add_source(config::File::with_name(name)).build();
-
-        let config = match config_result {
-            Ok(config) => config.try_deserialize::<GeneratorConfig>(),
-            // If the file is not found, use the default configuration
-            Err(config::ConfigError::Foreign(err))
-                if err
-                    .downcast_ref::<io::Error>()
-                    .map_or(false, |io_err| io_err.kind() == io::ErrorKind::NotFound) =>
-            {
+        let path = [name.to_owned()]
+            .into_iter()
+            .chain(["json", "toml", "yaml", "yml"].map(|ext| format!("{name}.{ext}")))
+            .map(PathBuf::from)
+            .find(|path| path.is_file());
+
+        let config = match path {
+            Some(path) => load::ConfigFile::load(path)?.to_config()?,
+            None => {
                 info!("No configuration file found, using default configuration");
-                Ok(GeneratorConfig::default())
+                GeneratorConfig::default()
             }
-            Err(e) => Err(e),
         };
 
-        debug!(config = ?config,"Loaded configuration");
+        debug!(config = ?config, "Loaded configuration");
+
+        Ok(config)
+    }
 
-        Ok(config?)
+    /// Search `cwd` and its parent directories for a config file, see [`load::find_config`].
+    /// If none is found, use the default configuration
+    pub fn discover(cwd: impl AsRef<Path>) -> Result<Self, GeneratorConfigError> {
+        match load::ConfigFile::discover(cwd)? {
+            Some(file) => file.to_config(),
+            None => {
+                info!("No configuration file found, using default configuration");
+                Ok(GeneratorConfig::default())
+            }
+        }
     }
 
     pub fn from_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
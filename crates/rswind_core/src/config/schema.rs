@@ -1,9 +1,24 @@
+use std::collections::BTreeMap;
+
 use rswind_common::impl_schemars;
 use rswind_css::rule::RuleList;
 
-use crate::{parse::AdditionalCssHandler, process::RuleMatchingFn, types::TypeValidator};
+use crate::{parse::AdditionalCssHandler, process::TryRuleMatchingFn, types::TypeValidator};
+
+/// A css template of a utility
+#[allow(dead_code)]
+#[derive(schemars::JsonSchema)]
+#[serde(untagged)]
+enum CssTemplate {
+    /// A template string, `$0` or `${0}` for the value, `$1` or `${1}` for the modifier,
+    /// `${raw}` for the value as written in the candidate,
+    /// `${1:-fallback}` for a fallback and `${1:+text}` for a conditional text
+    Template(String),
+    /// A nested rule, e.g. `&::placeholder`
+    Rule(BTreeMap<String, CssTemplate>),
+}
 
-impl_schemars!(dyn RuleMatchingFn => std::collections::HashMap<String, String>);
+impl_schemars!(dyn TryRuleMatchingFn => BTreeMap<String, CssTemplate>);
 
 impl_schemars!(dyn TypeValidator => String);
 
@@ -0,0 +1,436 @@
+//! Validating a config, collecting every invalid value with its JSON pointer and location
+
+use std::{
+    fmt::{self, Display},
+    path::PathBuf,
+};
+
+use rswind_theme::ThemeConfig;
+use serde::{
+    de::{self, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use serde_json::{json, Value};
+
+use super::{GeneratorConfig, PluginConfig, StaticUtilityValue, StylesheetConfig};
+use crate::parse::UtilityBuilder;
+
+/// Top level keys which are not part of [`GeneratorConfig`]
+static EXTRA_KEYS: &[&str] = &["$schema"];
+
+/// An invalid value in a config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDiagnostic {
+    /// JSON pointer to the value, e.g. `/utilities/0/css`
+    pub pointer: String,
+    pub message: String,
+    /// The file defining the value
+    pub file: Option<PathBuf>,
+    /// Line and column of the value, only known for JSON files, TOML and YAML files have none
+    pub position: Option<(usize, usize)>,
+}
+
+impl Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.position) {
+            (Some(file), Some((line, column))) => {
+                write!(f, "{}:{line}:{column}: ", file.display())?
+            }
+            (Some(file), None) => write!(f, "{}: ", file.display())?,
+            _ => (),
+        }
+        write!(f, "{} at `{}`", self.message, self.pointer)
+    }
+}
+
+/// Check each field of a config separately, so that all invalid values are reported
+pub fn validate(config: &Value) -> Vec<ConfigDiagnostic> {
+    let mut validator = Validator::default();
+    let Some(config) = config.as_object() else {
+        validator.error("", "expected an object");
+        return validator.diagnostics;
+    };
+
+    let fields = struct_fields::<GeneratorConfig>();
+    for (key, value) in config {
+        let pointer = push_pointer("", key);
+        match key.as_str() {
+            "theme" => validator.theme(value, &pointer),
+            "utilities" => validator.each(value, &pointer, Validator::utility),
+            "staticUtilities" => validator.entries(value, &pointer, |validator, value, pointer| {
+                validator.check::<StaticUtilityValue>(value, pointer)
+            }),
+            "plugins" => validator.each(value, &pointer, |validator, value, pointer| {
+                validator.check::<PluginConfig>(value, pointer)
+            }),
+            "stylesheets" => validator.each(value, &pointer, |validator, value, pointer| {
+                validator.check::<StylesheetConfig>(value, pointer)
+            }),
+            key if EXTRA_KEYS.contains(&key) => (),
+            key if fields.contains(&key) => {
+                validator.check_owned::<GeneratorConfig>(json!({ key: value }), &pointer)
+            }
+            key => validator.error(
+                &pointer,
+                format!("unknown field `{key}`, expected one of {}", one_of(fields)),
+            ),
+        }
+    }
+
+    validator.diagnostics
+}
+
+#[derive(Default)]
+struct Validator {
+    diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl Validator {
+    fn error(&mut self, pointer: &str, message: impl Into<String>) {
+        self.diagnostics.push(ConfigDiagnostic {
+            pointer: pointer.into(),
+            message: message.into(),
+            file: None,
+            position: None,
+        });
+    }
+
+    fn check<'a, T: Deserialize<'a>>(&mut self, value: &'a Value, pointer: &str) {
+        if let Err(e) = T::deserialize(value) {
+            self.error(pointer, e.to_string());
+        }
+    }
+
+    fn each(&mut self, value: &Value, pointer: &str, f: impl Fn(&mut Self, &Value, &str)) {
+        match value {
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    f(self, item, &push_pointer(pointer, &i.to_string()));
+                }
+            }
+            _ => self.error(pointer, "expected an array"),
+        }
+    }
+
+    fn entries(&mut self, value: &Value, pointer: &str, f: impl Fn(&mut Self, &Value, &str)) {
+        match value {
+            Value::Object(entries) => {
+                for (key, value) in entries {
+                    f(self, value, &push_pointer(pointer, key));
+                }
+            }
+            _ => self.error(pointer, "expected an object"),
+        }
+    }
+
+    /// Check each theme key separately, e.g. `/theme/extend/colors`
+    fn theme(&mut self, value: &Value, pointer: &str) {
+        self.entries(value, pointer, |validator, value, pointer| {
+            let key = pointer.rsplit('/').next().unwrap_or_default();
+            if key == "extend" {
+                validator.entries(value, pointer, |validator, value, pointer| {
+                    let key = unescape(pointer.rsplit('/').next().unwrap_or_default());
+                    validator
+                        .check_owned::<ThemeConfig>(json!({ "extend": { key: value } }), pointer)
+                });
+            } else {
+                validator.check_owned::<ThemeConfig>(json!({ unescape(key): value }), pointer);
+            }
+        });
+    }
+
+    fn check_owned<T: for<'a> Deserialize<'a>>(&mut self, value: Value, pointer: &str) {
+        self.check::<T>(&value, pointer);
+    }
+
+    fn utility(&mut self, value: &Value, pointer: &str) {
+        let Some(utility) = value.as_object() else {
+            return self.error(pointer, "expected an object");
+        };
+
+        for field in ["key", "css"] {
+            if !utility.contains_key(field) {
+                self.error(pointer, format!("missing field `{field}`"));
+            }
+        }
+
+        // each field is checked alone, with a placeholder for the required `key`
+        for (key, value) in utility {
+            let field = match key.as_str() {
+                "key" => json!({ "key": value }),
+                _ => json!({ "key": "", key: value }),
+            };
+            self.check_owned::<UtilityBuilder>(field, &push_pointer(pointer, key));
+        }
+    }
+}
+
+/// The field names of a struct deriving [`Deserialize`], as listed in its implementation
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    struct FieldsDeserializer<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldsDeserializer<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("expected a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            fields: &'static [&'static str],
+            _: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("only the fields are read"))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsDeserializer(&mut fields));
+    fields
+}
+
+/// Format names like serde does, e.g. `` `a`, `b` ``
+fn one_of(names: &[&str]) -> String {
+    names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ")
+}
+
+/// Append a key to a JSON pointer, escaping `~` and `/`
+fn push_pointer(pointer: &str, key: &str) -> String {
+    format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn unescape(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// The byte offset of the value at `pointer` in a JSON document
+pub fn locate_json(source: &str, pointer: &str) -> Option<usize> {
+    let mut parser = JsonLocator { source, pos: 0 };
+    parser.skip_whitespace();
+    for segment in pointer.split('/').skip(1).map(unescape) {
+        match parser.peek()? {
+            b'{' => parser.find_key(&segment)?,
+            b'[' => parser.find_index(segment.parse().ok()?)?,
+            _ => return None,
+        }
+    }
+    Some(parser.pos)
+}
+
+/// 1-based line and column of a byte offset
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+struct JsonLocator<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonLocator<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip a punctuation like `:` or `,` and the whitespace around it
+    fn skip_punct(&mut self, punct: u8) -> Option<()> {
+        self.skip_whitespace();
+        (self.peek()? == punct).then(|| self.pos += 1)?;
+        self.skip_whitespace();
+        Some(())
+    }
+
+    fn string(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        (self.peek()? == b'"').then_some(())?;
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some(&self.source[start..self.pos]);
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        let mut depth = 0usize;
+        loop {
+            match self.peek()? {
+                b'"' => {
+                    self.string()?;
+                }
+                b'{' | b'[' => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                b'}' | b']' if depth == 0 => return Some(()),
+                b'}' | b']' => {
+                    depth -= 1;
+                    self.pos += 1;
+                }
+                b',' if depth == 0 => return Some(()),
+                _ => self.pos += 1,
+            }
+            if depth == 0 && matches!(self.source.as_bytes()[self.pos - 1], b'"' | b'}' | b']') {
+                return Some(());
+            }
+        }
+    }
+
+    fn find_key(&mut self, key: &str) -> Option<()> {
+        self.skip_punct(b'{')?;
+        loop {
+            let raw = self.string()?;
+            let found = serde_json::from_str::<String>(raw).ok()? == key;
+            self.skip_punct(b':')?;
+            if found {
+                return Some(());
+            }
+            self.skip_value()?;
+            self.skip_punct(b',')?;
+        }
+    }
+
+    fn find_index(&mut self, index: usize) -> Option<()> {
+        self.skip_punct(b'[')?;
+        for _ in 0..index {
+            self.skip_value()?;
+            self.skip_punct(b',')?;
+        }
+        (!matches!(self.peek()?, b']')).then_some(())
+    }
+}
+
+/// A file defining a config or a config it extends
+pub(crate) struct Source {
+    pub path: PathBuf,
+    pub value: Value,
+    /// The text of a JSON file, and the pointer to the config in it, e.g. `/rswind` in `package.json`
+    pub json: Option<(String, &'static str)>,
+}
+
+/// Fill in the file and position of a diagnostic from the sources of a config
+pub(crate) fn locate(diagnostic: &mut ConfigDiagnostic, merged: &Value, sources: &[Source]) {
+    let pointer = diagnostic.pointer.as_str();
+    let Some((source, pointer)) = sources
+        .iter()
+        .find_map(|source| Some((source, source_pointer(source, merged, pointer)?)))
+        .or_else(|| {
+            let source = sources.iter().find(|source| source.value.pointer(pointer).is_some());
+            Some((source.or(sources.first())?, pointer.to_owned()))
+        })
+    else {
+        return;
+    };
+
+    diagnostic.file = Some(source.path.clone());
+    diagnostic.position = source.json.as_ref().and_then(|(text, prefix)| {
+        locate_json(text, &format!("{prefix}{pointer}")).map(|offset| line_column(text, offset))
+    });
+}
+
+/// The pointer to the same value as `pointer` in `merged`, if `source` defines it.
+/// Top level arrays like `utilities` are concatenated, so their indexes may differ
+fn source_pointer(source: &Source, merged: &Value, pointer: &str) -> Option<String> {
+    let value = merged.pointer(pointer)?;
+    let mut segments = pointer.splitn(4, '/').skip(1);
+    if let (Some(key), Some(index)) = (segments.next(), segments.next()) {
+        let top = format!("/{key}");
+        if let (Some(Value::Array(_)), Some(Value::Array(source_items))) =
+            (merged.pointer(&top), source.value.pointer(&top))
+        {
+            let item = merged.pointer(&format!("{top}/{index}"))?;
+            let index = source_items.iter().position(|source_item| source_item == item)?;
+            let rest = segments.next().map_or(String::new(), |rest| format!("/{rest}"));
+            return Some(format!("{top}/{index}{rest}"));
+        }
+    }
+
+    (source.value.pointer(pointer) == Some(value)).then(|| pointer.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let source = r##"{
+  "$schema": "./schema.json",
+  "content": ["./src/**/*.html"],
+  "darkMode": 1,
+  "theme": {
+    "extend": { "colors": { "primary": "#3490dc" }, "spacing": [] }
+  },
+  "utilities": [
+    { "key": "foo", "css": { "color": "$1" }, "theme": "colors" },
+    { "key": "bar", "order": "not-a-key", "type": "not-a-type" },
+    { "key": "baz", "css": { "color": "$1" }, "unknown": true }
+  ],
+  "utilites": []
+}"##;
+        let value: Value = serde_json::from_str(source).unwrap();
+        let mut diagnostics = validate(&value);
+        let sources = [Source {
+            path: PathBuf::from("rswind.config.json"),
+            value: value.clone(),
+            json: Some((source.to_owned(), "")),
+        }];
+        diagnostics.iter_mut().for_each(|d| locate(d, &value, &sources));
+
+        let res = diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.position.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            res,
+            [
+                ("/darkMode", (4, 15)),
+                ("/theme/extend/spacing", (6, 64)),
+                ("/utilites", (13, 15)),
+                ("/utilities/1", (10, 5)),
+                ("/utilities/1/order", (10, 30)),
+                ("/utilities/1/type", (10, 51)),
+                ("/utilities/2/unknown", (11, 58)),
+            ]
+        );
+        assert!(diagnostics[2].message.starts_with("unknown field `utilites`, expected one of"));
+        assert_eq!(
+            diagnostics[3].to_string(),
+            "rswind.config.json:10:5: missing field `css` at `/utilities/1`"
+        );
+        let unknown = &diagnostics[6].message;
+        assert!(unknown.starts_with("unknown field `unknown`, expected one of `key`"), "{unknown}");
+    }
+
+    #[test]
+    fn test_locate_json() {
+        let source = r#"{ "a/b": [1, { "c": "x\"y" }, [2, 3]], "d": { "e": null } }"#;
+        assert_eq!(locate_json(source, "/a~1b/1/c"), Some(20));
+        assert_eq!(locate_json(source, "/a~1b/2/1"), Some(34));
+        assert_eq!(locate_json(source, "/d/e"), Some(51));
+        assert_eq!(locate_json(source, "/d/f"), None);
+        assert_eq!(locate_json(source, "/a~1b/3"), None);
+    }
+}
@@ -1,22 +1,26 @@
-use std::{cmp::Ordering, fmt::Write, sync::Arc};
+use std::{borrow::Cow, cmp::Ordering, fmt::Write, sync::Arc};
 
 use cssparser::serialize_name;
 use derive_more::{Deref, DerefMut};
-use rswind_css::rule::RuleList;
+use phf::phf_map;
+use rswind_css::{rule::RuleList, Rule};
 use rswind_theme::{Theme, ThemeMap};
 use smallvec::SmallVec;
 use smol_str::SmolStr;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use self::{
     utilities::{StaticUtility, UtilityStorage},
     variants::VariantStorage,
 };
 use crate::{
-    common::{StrReplaceExt, StrSplitExt},
+    common::{MaybeArbitrary, StrReplaceExt, StrSplitExt},
     ordering::OrderingKey,
     parse::{candidate::CandidateParser, UtilityCandidate, VariantCandidate},
-    process::{Utility, UtilityApplyResult, UtilityGroup, VariantOrdering},
+    process::{
+        theme_function::resolve_theme_functions, Utility, UtilityApplyResult, UtilityGroup,
+        ValueDef, VariantOrdering,
+    },
 };
 
 pub mod utilities;
@@ -32,6 +36,9 @@ pub struct DesignSystem {
 
     /// Theme values
     pub theme: Theme,
+
+    /// Base styles, always generated before utilities
+    pub base: RuleList,
 }
 
 impl Extend<(SmolStr, Utility)> for DesignSystem {
@@ -139,6 +146,7 @@ impl DesignSystem {
             variants: VariantStorage::default(),
             utilities: UtilityStorage::default(),
             theme: Theme::default(),
+            base: RuleList::default(),
         }
     }
 
@@ -169,6 +177,38 @@ impl DesignSystem {
         self
     }
 
+    /// Add a static utility with nested rules, which can't be expressed by [`StaticUtility`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rswind_core::DesignSystem;
+    /// use rswind_css::{rule::RuleList, Decl, Rule, ToCssString};
+    ///
+    /// let mut design = DesignSystem::new();
+    ///
+    /// design.add_static_rule(
+    ///     "link",
+    ///     Rule {
+    ///         selector: "&".into(),
+    ///         decls: vec![Decl::new("color", "blue")],
+    ///         rules: RuleList::new(Rule::new_with_decls("&:hover", vec![Decl::new("color", "red")])),
+    ///     },
+    /// );
+    ///
+    /// let res = design.generate("link").unwrap();
+    ///
+    /// assert_eq!(res.rule.to_css_minified(), ".link{color:blue;&:hover{color:red;}}");
+    /// ```
+    pub fn add_static_rule(&mut self, key: impl Into<SmolStr>, rule: Rule) -> &mut Self {
+        static DEFAULT_VALUE: phf::Map<&str, &str> = phf_map! { "DEFAULT" => "" };
+
+        let mut utility = Utility::new(move |_, _| rule.clone());
+        utility.value_def = ValueDef::new(ThemeMap::Static(&DEFAULT_VALUE));
+        self.utilities.add(key.into(), utility);
+        self
+    }
+
     /// Add a static variant
     ///
     ///
@@ -217,6 +257,25 @@ impl DesignSystem {
         self
     }
 
+    /// Add base styles, which are generated before all utilities
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rswind_core::DesignSystem;
+    /// use rswind_css::{Decl, Rule, ToCssString};
+    ///
+    /// let mut design = DesignSystem::new();
+    ///
+    /// design.add_base(Rule::new_with_decls("select", vec![Decl::new("appearance", "none")]));
+    ///
+    /// assert_eq!(design.base.to_css_minified(), "select{appearance:none;}");
+    /// ```
+    pub fn add_base(&mut self, rule: Rule) -> &mut Self {
+        self.base.0.push(rule);
+        self
+    }
+
     pub fn add_utility(&mut self, key: &str, utility: Utility) {
         self.utilities.add(key.into(), utility);
     }
@@ -225,6 +284,28 @@ impl DesignSystem {
         self.theme.get(key).cloned()
     }
 
+    /// Resolve `theme()` functions in an arbitrary value of `candidate`,
+    /// an unknown theme value is reported and makes the candidate invalid
+    fn resolve_arbitrary<'a>(&self, candidate: &str, raw: &'a str) -> Option<Cow<'a, str>> {
+        resolve_theme_functions(raw, &self.theme)
+            .map_err(|e| warn!("{} in `{}`", e, candidate))
+            .ok()
+    }
+
+    /// Split a candidate into its variants and the key of its utility in [`UtilityStorage`],
+    /// e.g. `md:hover:bg-red-500` into `["md", "hover"]` and `bg`
+    pub fn split_candidate<'a>(&self, value: &'a str) -> Option<(SmallVec<[&'a str; 2]>, &'a str)> {
+        if self.utilities.get(value).is_some() {
+            return Some((SmallVec::new(), value));
+        }
+
+        let mut parts: SmallVec<[&str; 2]> = value.split_toplevel(b':')?;
+        let utility = parts.pop()?;
+        let key = CandidateParser::new(utility).parse_utility(&self.utilities)?.key;
+
+        Some((parts, key))
+    }
+
     /// Try generate a utility with the given value
     pub fn generate(&self, value: &str) -> Option<GeneratedUtility> {
         // Try static utility first
@@ -244,7 +325,18 @@ impl DesignSystem {
         let mut parts: SmallVec<[&str; 2]> = value.split_toplevel(b':')?;
         let utility = parts.pop()?;
 
-        let utility_candidate = CandidateParser::new(utility).parse_utility(&self.utilities)?;
+        let mut utility_candidate = CandidateParser::new(utility).parse_utility(&self.utilities)?;
+
+        // `w-[calc(theme(spacing.4)+2px)]`, the resolved values need to outlive the candidate
+        let (arbitrary_value, arbitrary_modifier);
+        if let Some(MaybeArbitrary::Arbitrary(raw)) = utility_candidate.value {
+            arbitrary_value = self.resolve_arbitrary(value, raw)?;
+            utility_candidate.value = Some(MaybeArbitrary::Arbitrary(&arbitrary_value));
+        }
+        if let Some(MaybeArbitrary::Arbitrary(raw)) = utility_candidate.modifier {
+            arbitrary_modifier = self.resolve_arbitrary(value, raw)?;
+            utility_candidate.modifier = Some(MaybeArbitrary::Arbitrary(&arbitrary_modifier));
+        }
 
         debug!(?utility_candidate);
 
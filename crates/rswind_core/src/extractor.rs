@@ -0,0 +1,124 @@
+use std::{borrow::Cow, path::Path};
+
+use globset::Glob;
+use rswind_common::iter::prelude::*;
+use rswind_extractor::{CustomExtractor, ExtractOptions, Extractable, Extractor, InputKind};
+use rustc_hash::FxHashSet as HashSet;
+use smol_str::SmolStr;
+
+use crate::{generator::GeneratorInput, glob::BuildGlobError, io::FileInput};
+
+/// Which files a custom extractor applies to
+#[derive(Debug, Clone)]
+enum ExtractorMatcher {
+    /// A file extension without the leading `.`, e.g. `tpl`
+    Extension(SmolStr),
+    /// A glob matched against the path relative to the base directory, e.g. `views/**/*.html`
+    Glob(globset::GlobMatcher),
+}
+
+impl ExtractorMatcher {
+    fn is_match(&self, path: &Path) -> bool {
+        match self {
+            Self::Extension(ext) => path.extension().is_some_and(|e| e == ext.as_str()),
+            Self::Glob(glob) => glob.is_match(path),
+        }
+    }
+}
+
+/// Custom extractors registered per file extension or glob
+///
+/// The first matching extractor wins, files without a match use the builtin extractors.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractorRegistry {
+    extractors: Vec<(ExtractorMatcher, CustomExtractor)>,
+    base: Option<Box<Path>>,
+}
+
+impl ExtractorRegistry {
+    /// Register an extractor for `pattern`, an extension like `tpl` or `.tpl`, or a glob like `**/*.tpl`
+    pub fn add(&mut self, pattern: &str, extractor: CustomExtractor) -> Result<(), BuildGlobError> {
+        let matcher = match pattern.contains(['*', '?', '[', '{', '/']) {
+            true => ExtractorMatcher::Glob(
+                Glob::new(pattern.trim_start_matches("./"))?.compile_matcher(),
+            ),
+            false => ExtractorMatcher::Extension(pattern.trim_start_matches('.').into()),
+        };
+        self.extractors.push((matcher, extractor));
+        Ok(())
+    }
+
+    /// Set the base directory globs are relative to
+    pub fn with_base(mut self, base: &Path) -> Self {
+        self.base = Some(base.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.extractors.is_empty()
+    }
+
+    pub fn get(&self, path: &str) -> Option<&CustomExtractor> {
+        let path = Path::new(path);
+        let relative = self.base.as_deref().and_then(|base| path.strip_prefix(base).ok());
+        let path = relative.unwrap_or(path);
+
+        self.extractors
+            .iter()
+            .find(|(matcher, _)| matcher.is_match(path))
+            .map(|(_, extractor)| extractor)
+    }
+
+    /// Create an [`Extractor`] for a file, using the custom extractor if one matches
+    pub fn extractor<'a>(&self, input: GeneratorInput<'a>) -> Extractor<'a> {
+        match self.get(input.path) {
+            Some(extractor) => Extractor::new(input.content, InputKind::Custom(extractor.clone())),
+            None => input.into(),
+        }
+    }
+
+    /// Extract the candidates of each file, in the order of `files`,
+    /// e.g. with the [`Generator::iter_kind`](crate::generator::Generator::iter_kind) of an app
+    pub fn extract_files<'a>(
+        &self,
+        files: &'a [FileInput],
+        options: &'a ExtractOptions,
+        kind: IntoIterKind,
+    ) -> Vec<HashSet<Cow<'a, str>>> {
+        files
+            .into_iter_with(kind)
+            .map(|f| self.extractor(f.into()).with_options(options).extract())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rswind_extractor::Extractable;
+
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let mut registry = ExtractorRegistry::default().with_base(Path::new("/app"));
+        let split =
+            |sep| CustomExtractor::new(move |input| input.split(sep).map(Into::into).collect());
+        registry.add(".tpl", split(',')).unwrap();
+        registry.add("legacy/**/*.html", split('|')).unwrap();
+
+        let mut res = registry
+            .extractor(("/app/a.tpl", "flex,p-4").into())
+            .extract()
+            .into_iter()
+            .collect::<Vec<_>>();
+        res.sort();
+        assert_eq!(res, ["flex", "p-4"]);
+
+        let res = registry.extractor(("/app/legacy/b/c.html", "m-2|grid").into()).extract();
+        assert!(res.contains("m-2") && res.contains("grid"));
+
+        let res =
+            registry.extractor(("/app/src/c.html", r#"<div class="m-2|grid">"#).into()).extract();
+        assert!(res.contains("m-2|grid"));
+    }
+}
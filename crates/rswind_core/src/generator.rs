@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     env,
     path::{Path, PathBuf},
     sync::Arc,
@@ -8,22 +9,29 @@ use crate::{
     cache::{CacheState, GeneratorCache},
     common::{LoadPreset, LoadTheme},
     config::{GeneratorConfig, GeneratorConfigError},
-    glob::{BuildGlobError, GlobMatcher, MaybeParallelGlobFilter},
-    io::{walk, FileInput},
+    extractor::ExtractorRegistry,
+    glob::{BuildGlobError, GlobMatcher, DEFAULT_GLOB},
+    io::{read_content, walk, FileInput},
+    plugin::{Plugin, PluginError, PluginFactory, PluginRegistry},
     process::ThemeParseError,
-    processor::{GenOptions, GenerateResult, GeneratorProcessor, ParGenerateWith},
+    processor::{GenOptions, GenerateResult, GeneratorProcessor},
+    stylesheet::StylesheetError,
     DesignSystem,
 };
 use rswind_common::iter::prelude::*;
-use rswind_extractor::{Extractor, MaybeParCollectExtracted};
+use rswind_extractor::{CustomExtractor, ExtractOptions, Extractor, MaybeParCollectExtracted};
 
 use rswind_theme::Theme;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use smol_str::SmolStr;
 use thiserror::Error;
 use tracing::instrument;
 
 pub struct Generator {
     pub processor: GeneratorProcessor,
     pub glob: GlobMatcher,
+    pub extract: ExtractOptions,
+    pub extractors: ExtractorRegistry,
 }
 
 #[derive(Default)]
@@ -32,6 +40,10 @@ pub struct GeneratorBuilder {
     pub(crate) design: DesignSystem,
     pub(crate) presets: Vec<Box<dyn LoadPreset>>,
     pub(crate) themes: Vec<Box<dyn LoadTheme>>,
+    pub(crate) plugins: PluginRegistry,
+    pub(crate) plugin_factories: HashMap<SmolStr, PluginFactory>,
+    pub(crate) plugins_configured: bool,
+    pub(crate) extractors: Vec<(SmolStr, CustomExtractor)>,
     pub(crate) options: GenOptions,
     pub(crate) base: Option<String>,
 }
@@ -46,6 +58,10 @@ pub enum AppBuildError {
     IoError(#[from] std::io::Error),
     #[error("{0}")]
     ConfigError(#[from] GeneratorConfigError),
+    #[error("Failed to load plugins: {0}")]
+    PluginError(#[from] PluginError),
+    #[error("Failed to process stylesheet: {0}")]
+    StylesheetError(#[from] StylesheetError),
 }
 
 #[cfg(feature = "napi")]
@@ -78,6 +94,47 @@ impl GeneratorBuilder {
         self
     }
 
+    #[instrument(skip_all)]
+    pub fn with_plugin(mut self, plugin: impl Plugin + 'static) -> Self {
+        self.plugins.add(plugin);
+        self
+    }
+
+    /// Register a plugin which can be enabled by name in config `plugins`
+    #[instrument(skip_all)]
+    pub fn with_plugin_factory(mut self, name: impl Into<SmolStr>, factory: PluginFactory) -> Self {
+        self.plugin_factories.insert(name.into(), factory);
+        self
+    }
+
+    #[instrument(skip_all)]
+    pub fn with_plugin_factories(
+        mut self,
+        factories: impl IntoIterator<Item = (&'static str, PluginFactory)>,
+    ) -> Self {
+        self.plugin_factories.extend(factories.into_iter().map(|(k, v)| (SmolStr::from(k), v)));
+        self
+    }
+
+    /// Register a custom extractor for files matching `pattern`,
+    /// an extension like `tpl` or a glob relative to the base directory like `views/**/*.html`
+    ///
+    /// ```rust
+    /// use rswind_core::Generator;
+    ///
+    /// let builder = Generator::builder()
+    ///     .with_extractor("tpl", |input| input.split(['\n', ' ', ',']).map(Into::into).collect());
+    /// ```
+    #[instrument(skip_all)]
+    pub fn with_extractor(
+        mut self,
+        pattern: impl Into<SmolStr>,
+        extractor: impl for<'a> Fn(&'a str) -> HashSet<Cow<'a, str>> + Send + Sync + 'static,
+    ) -> Self {
+        self.extractors.push((pattern.into(), CustomExtractor::new(extractor)));
+        self
+    }
+
     pub fn with_base(mut self, base: Option<String>) -> Self {
         self.base = base;
         self
@@ -93,13 +150,50 @@ impl GeneratorBuilder {
         self
     }
 
+    /// Create plugins enabled in config, resolve plugin order and run their `configure` hooks, only once
+    fn configure_plugins(&mut self) -> Result<(), PluginError> {
+        if self.plugins_configured {
+            return Ok(());
+        }
+        self.plugins_configured = true;
+
+        if let Some(config) = &self.config {
+            for plugin in config.plugins.iter() {
+                let factory = self
+                    .plugin_factories
+                    .get(plugin.name())
+                    .ok_or_else(|| PluginError::Unknown(plugin.name().into()))?;
+                self.plugins.add_boxed(factory(plugin.options())?);
+            }
+        }
+
+        if self.plugins.is_empty() {
+            return Ok(());
+        }
+
+        self.plugins.resolve()?;
+
+        let config = self.config.get_or_insert_with(GeneratorConfig::default);
+        for plugin in self.plugins.iter() {
+            plugin.configure(config);
+        }
+
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     pub fn build_processor(mut self) -> Result<GeneratorProcessor, AppBuildError> {
+        self.configure_plugins()?;
+
         // Step 1: load theme
         for theme in self.themes.drain(..) {
             theme.load_theme(&mut self.design);
         }
 
+        for plugin in self.plugins.iter() {
+            plugin.theme(&mut self.design);
+        }
+
         if let Some(ref mut config) = self.config {
             self.design.theme.merge(&mut config.theme);
             for utility in config.utilities.drain(..) {
@@ -117,6 +211,11 @@ impl GeneratorBuilder {
             preset.load_preset(&mut self.design);
         }
 
+        for plugin in self.plugins.iter() {
+            plugin.utilities(&mut self.design);
+            plugin.variants(&mut self.design);
+        }
+
         Ok(GeneratorProcessor {
             design: Arc::new(self.design),
             cache: GeneratorCache::new(match self.options.watch {
@@ -124,29 +223,41 @@ impl GeneratorBuilder {
                 false => CacheState::OneShot,
             }),
             options: self.options,
+            plugins: self.plugins.into_list(),
         })
     }
 
     #[instrument(skip_all)]
     pub fn build(mut self) -> Result<Generator, AppBuildError> {
+        self.configure_plugins()?;
+
         let base = self.base.take().map_or(env::current_dir()?, PathBuf::from);
 
+        let exclude =
+            self.config.as_mut().map(|c| std::mem::take(&mut c.exclude)).unwrap_or_default();
         let glob = match self.config {
             Some(ref mut config) if !config.content.is_empty() => {
-                GlobMatcher::new(config.content.drain(..), base)?
+                GlobMatcher::with_exclude(config.content.drain(..), exclude, base)?
             }
-            _ => GlobMatcher::default_glob(base)?,
+            _ => GlobMatcher::with_exclude(DEFAULT_GLOB.iter().copied(), exclude, base)?,
         };
 
+        let extract = self.config.as_ref().map(|c| c.extract.to_options()).unwrap_or_default();
+
+        let mut extractors = ExtractorRegistry::default().with_base(glob.base());
+        for (pattern, extractor) in self.extractors.drain(..) {
+            extractors.add(&pattern, extractor)?;
+        }
+
         let processor = self.build_processor()?;
 
-        Ok(Generator { processor, glob })
+        Ok(Generator { processor, glob, extract, extractors })
     }
 }
 
 pub struct GeneratorInput<'a> {
-    path: &'a str,
-    content: &'a str,
+    pub(crate) path: &'a str,
+    pub(crate) content: &'a str,
 }
 
 impl AsRef<Path> for GeneratorInput<'_> {
@@ -192,19 +303,51 @@ impl Generator {
         self.glob.base()
     }
 
+    /// Whether files are read and extracted in parallel
+    pub fn iter_kind(&self) -> IntoIterKind {
+        match self.processor.options.parallel {
+            true => IntoIterKind::Parallel,
+            false => IntoIterKind::Sequential,
+        }
+    }
+
+    /// Walk the files matched by `content`
+    pub fn walk_contents(&self) -> Vec<PathBuf> {
+        walk(&self.glob)
+    }
+
+    /// Read `paths`, files that can not be read are skipped
+    pub fn read_files(&self, paths: Vec<PathBuf>) -> Vec<FileInput> {
+        paths.into_iter_with(self.iter_kind()).flat_map(read_content).collect()
+    }
+
+    /// Walk and read the files matched by `content`, files that can not be read are skipped
+    pub fn read_contents(&self) -> Vec<FileInput> {
+        self.read_files(self.walk_contents())
+    }
+
     pub fn generate_contents(&mut self) -> GenerateResult {
-        walk(self.base())
-            .into_iter_with(IntoIterKind::Parallel)
-            .glob_filter(&self.glob)
-            .map(FileInput::from_file)
-            .collect::<Vec<_>>()
-            .iter_with(IntoIterKind::Parallel)
-            .map(GeneratorInput::from)
-            .collect_extracted()
-            .par_generate_with(&mut self.processor)
+        let files = self.read_contents();
+        self.generate_files(&files)
+    }
+
+    /// Generate the utilities used in `files`, e.g. files from [`Self::read_contents`]
+    pub fn generate_files(&mut self, files: &[FileInput]) -> GenerateResult {
+        let kind = self.iter_kind();
+        let (extractors, extract) = (&self.extractors, &self.extract);
+        let candidates = files
+            .into_iter_with(kind)
+            .map(|f| extractors.extractor(f.into()).with_options(extract))
+            .collect_extracted();
+
+        self.processor.run(candidates)
     }
 }
 
+/// The extension of a file, compound extensions of templates like `blade.php` are kept
 fn get_extension(path: &str) -> &str {
+    if path.ends_with(".blade.php") {
+        return "blade.php";
+    }
     Path::new(path).extension().unwrap_or_default().to_str().unwrap_or_default()
 }
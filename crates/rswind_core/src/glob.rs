@@ -1,51 +1,86 @@
-use std::path::{Component, Path, PathBuf};
+use std::{
+    borrow::Cow,
+    path::{Component, Path, PathBuf},
+};
 
 use either::Either;
 use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use rayon::iter::ParallelIterator;
 use rswind_common::iter::MaybeParallelIterator;
 use thiserror::Error;
+use tracing::warn;
 
-pub static DEFAULT_GLOB: &[&str] = &["./**/*.{html,js,jsx,mjs,cjs,ts,tsx,mts,cts,vue,svelte,mdx}"];
+/// Directory names always skipped at any depth below a walk root, in addition to `.gitignore` and `exclude`
+pub static DEFAULT_EXCLUDE: &[&str] = &["node_modules", ".git", "target"];
+
+pub static DEFAULT_GLOB: &[&str] =
+    &["./**/*.{html,js,jsx,mjs,cjs,ts,tsx,mts,cts,vue,svelte,astro,mdx}"];
 
 #[derive(Debug, Error)]
 pub enum ResolveError {
     #[error("Invalid path prefix: {0:?}")]
     InvalidPath(PathBuf),
-    #[error("Parent directory(..) is not allowed")]
-    ParentDirNotAllowed,
     #[error("Empty path")]
     EmptyPath,
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
-/// Make `base` as root directory and resolve `path` to it.
+/// Resolve `path` relative to `base`, absolute paths are kept as is.
 fn resolve_base(path: &str, mut base: PathBuf) -> Result<PathBuf, ResolveError> {
-    let components = Path::new(path).components();
-    let (first, rest) = {
-        let mut c = components.clone();
-        (c.next().ok_or(ResolveError::EmptyPath)?, c)
-    };
+    let first = Path::new(path).components().next().ok_or(ResolveError::EmptyPath)?;
 
     match first {
-        Component::CurDir => {
-            base.push(rest);
-            Ok(base)
-        }
-        Component::ParentDir => Err(ResolveError::ParentDirNotAllowed),
-        Component::Normal(_) => {
+        Component::CurDir | Component::ParentDir | Component::Normal(_) => {
             base.push(path);
-            Ok(base)
-        }
-        Component::RootDir => {
-            base.push(rest);
-            Ok(base)
+            Ok(normalize(&base))
         }
+        // `/abs/path` or `C:\\abs\\path`
+        Component::RootDir => Ok(normalize(Path::new(path))),
+        Component::Prefix(_) if Path::new(path).has_root() => Ok(normalize(Path::new(path))),
         Component::Prefix(p) => Err(ResolveError::InvalidPath(p.as_os_str().into())),
     }
 }
 
+/// Escape `path` if it is an existing file relative to `base`,
+/// so explicit paths like `app/[id]/page.tsx` are not read as globs
+pub fn escape_file<'a>(path: &'a str, base: &Path) -> Cow<'a, str> {
+    match base.join(path).is_file() {
+        true => Cow::Owned(globset::escape(path)),
+        false => Cow::Borrowed(path),
+    }
+}
+
+/// Lexically remove `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+    let mut res = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                res.pop();
+            }
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+/// The directory to walk for a resolved glob, the longest leading path without glob syntax
+fn walk_root(glob: &Path) -> PathBuf {
+    let mut root = PathBuf::new();
+    for component in glob.components() {
+        if component.as_os_str().to_str().is_some_and(|c| c.contains(['*', '?', '[', '{'])) {
+            return root;
+        }
+        root.push(component);
+    }
+    // a single file
+    root.pop();
+    root
+}
+
 #[derive(Debug, Error)]
 pub enum BuildGlobError {
     #[error("Glob error: {0}")]
@@ -56,41 +91,143 @@ pub enum BuildGlobError {
     InvalidEncoding,
 }
 
+/// Matches input files against `content` globs relative to a base directory
+///
+/// Globs starting with `!` and `exclude` globs are excluded, as well as files ignored by
+/// `.gitignore` or `.ignore` in the base directory or in a walk root outside it.
+/// Globs may point outside the base directory, e.g. `../../packages/ui/src/**/*.tsx`,
+/// each distinct directory they start from is a walk root. Excludes starting with `**`
+/// and [`DEFAULT_EXCLUDE`] apply below every walk root.
 #[derive(Debug, Clone)]
 pub struct GlobMatcher {
     glob: GlobSet,
+    exclude: GlobSet,
+    gitignores: Vec<Gitignore>,
     base: PathBuf,
+    roots: Vec<PathBuf>,
 }
 
 impl GlobMatcher {
     pub fn new<T: AsRef<str>>(
         glob: impl IntoIterator<Item = T>,
         base: PathBuf,
+    ) -> Result<Self, BuildGlobError> {
+        Self::with_exclude(glob, std::iter::empty::<&str>(), base)
+    }
+
+    pub fn with_exclude<T: AsRef<str>, E: AsRef<str>>(
+        glob: impl IntoIterator<Item = T>,
+        exclude: impl IntoIterator<Item = E>,
+        base: PathBuf,
     ) -> Result<Self, BuildGlobError> {
         let cwd = base.canonicalize().map_err(|e| BuildGlobError::Resolve(e.into()))?;
 
-        let glob_set = glob.into_iter().try_fold(GlobSetBuilder::new(), |mut acc, g| {
-            let resolved = resolve_base(g.as_ref(), cwd.clone())?;
-            acc.add(Glob::new(
+        let add = |set: &mut GlobSetBuilder, glob: &str, base: &Path| {
+            let resolved = resolve_base(glob, base.to_owned())?;
+            set.add(Glob::new(
                 resolved.as_os_str().to_str().ok_or(BuildGlobError::InvalidEncoding)?,
             )?);
-            Ok::<_, BuildGlobError>(acc)
-        })?;
+            Ok::<_, BuildGlobError>(resolved)
+        };
+        // `dist` and `dist/**` both exclude the directory and everything in it
+        let add_exclude = |set: &mut GlobSetBuilder, glob: &str, base: &Path| {
+            let dir = glob.strip_suffix("/**").unwrap_or(glob);
+            add(set, dir, base)?;
+            add(set, &format!("{dir}/**"), base).map(|_| ())
+        };
+
+        let mut glob_set = GlobSetBuilder::new();
+        let mut exclude_set = GlobSetBuilder::new();
+
+        let mut roots = Vec::new();
+        let mut excludes = Vec::new();
+        for glob in glob {
+            match glob.as_ref().strip_prefix('!') {
+                Some(negated) => excludes.push(negated.to_owned()),
+                None => roots.push(walk_root(&add(&mut glob_set, glob.as_ref(), &cwd)?)),
+            }
+        }
+        excludes.extend(exclude.into_iter().map(|glob| glob.as_ref().to_owned()));
 
-        Ok(Self { glob: glob_set.build()?, base: cwd })
+        // nested roots are walked by their parent
+        roots.sort_unstable();
+        roots.dedup_by(|root, parent| root.starts_with(parent));
+
+        // `**/dist` is not anchored, it is resolved against every root outside the base as well
+        let outside = roots.iter().filter(|root| !root.starts_with(&cwd));
+        let anchors = std::iter::once(&cwd).chain(outside).collect::<Vec<_>>();
+        for glob in &excludes {
+            match glob.starts_with("**") {
+                true => {
+                    for anchor in &anchors {
+                        add_exclude(&mut exclude_set, glob, anchor)?;
+                    }
+                }
+                false => add_exclude(&mut exclude_set, glob, &cwd)?,
+            }
+        }
+
+        Ok(Self {
+            glob: glob_set.build()?,
+            exclude: exclude_set.build()?,
+            gitignores: anchors.iter().map(|anchor| build_gitignore(anchor)).collect(),
+            base: cwd,
+            roots,
+        })
     }
 
     pub fn default_glob(base: PathBuf) -> Result<Self, BuildGlobError> {
         Self::new(DEFAULT_GLOB.iter().copied(), base)
     }
 
+    /// Whether a file or directory is excluded, excluded directories are not walked
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        self.has_default_excluded(path, is_dir)
+            || self.exclude.is_match(path)
+            || self.gitignores.iter().any(|gitignore| {
+                path.starts_with(gitignore.path())
+                    && gitignore.matched_path_or_any_parents(path, is_dir).is_ignore()
+            })
+    }
+
+    /// Whether `path` is in a directory of [`DEFAULT_EXCLUDE`] below its walk root,
+    /// matched by name so the root itself may be inside such a directory
+    fn has_default_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let root = self.roots.iter().chain([&self.base]).find(|root| path.starts_with(root));
+        let relative = root.map_or(path, |root| path.strip_prefix(root).unwrap_or(path));
+        let dirs = match is_dir {
+            true => relative,
+            false => relative.parent().unwrap_or(relative),
+        };
+        dirs.components().any(|c| DEFAULT_EXCLUDE.iter().any(|name| c.as_os_str() == *name))
+    }
+
     pub fn is_match(&self, path: &Path) -> bool {
-        self.glob.is_match(path)
+        self.glob.is_match(path) && !self.is_excluded(path, false)
     }
 
     pub fn base(&self) -> &Path {
         &self.base
     }
+
+    /// Distinct directories to walk and watch, none of them is inside another
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+}
+
+/// `.gitignore` and `.ignore` in `base`, nested ignore files are only handled by [`crate::io::walk`]
+fn build_gitignore(base: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(base);
+    for name in [".gitignore", ".ignore"] {
+        let path = base.join(name);
+        if path.is_file() {
+            if let Some(err) = builder.add(&path) {
+                warn!("Failed to read {}: {}", path.display(), err);
+            }
+        }
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
 }
 
 pub trait GlobFilter<T> {
@@ -140,3 +277,173 @@ where
         self.filter(|p| matcher.is_match(p.as_ref()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::io::walk;
+
+    #[test]
+    fn test_exclude() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().to_owned();
+        for dir in ["src/generated", "src/components", "dist", "node_modules/pkg"] {
+            fs::create_dir_all(base.join(dir)).unwrap();
+        }
+        for file in [
+            "src/index.html",
+            "src/index.test.ts",
+            "src/generated/a.ts",
+            "src/components/button.tsx",
+            "src/components/debug.tsx",
+            "dist/index.html",
+            "node_modules/pkg/index.js",
+        ] {
+            fs::write(base.join(file), "").unwrap();
+        }
+        fs::write(base.join(".gitignore"), "dist/\n").unwrap();
+        fs::write(base.join("src/components/.ignore"), "debug.tsx\n").unwrap();
+
+        let matcher = GlobMatcher::with_exclude(
+            ["./**/*.{html,ts,tsx,js}", "!**/*.test.ts"],
+            ["./src/generated"],
+            base.clone(),
+        )
+        .unwrap();
+        let base = matcher.base().to_owned();
+
+        assert!(matcher.is_excluded(&base.join("src/generated"), true));
+        assert!(matcher.is_excluded(&base.join("dist/index.html"), false));
+        assert!(!matcher.is_match(&base.join("src/index.test.ts")));
+        assert!(matcher.is_match(&base.join("src/index.html")));
+
+        let files = walk(&matcher)
+            .into_iter()
+            .map(|p| p.strip_prefix(&base).unwrap().to_str().unwrap().replace('\\', "/"))
+            .collect::<Vec<_>>();
+        assert_eq!(files, ["src/components/button.tsx", "src/index.html"]);
+    }
+
+    #[test]
+    fn test_resolve_base() {
+        let base = PathBuf::from("/app/web");
+        assert_eq!(resolve_base("./src/**", base.clone()).unwrap(), Path::new("/app/web/src/**"));
+        assert_eq!(
+            resolve_base("../../packages/ui/**", base.clone()).unwrap(),
+            Path::new("/packages/ui/**")
+        );
+        assert_eq!(resolve_base("/lib/*.html", base).unwrap(), Path::new("/lib/*.html"));
+
+        assert_eq!(walk_root(Path::new("/app/src/**/*.tsx")), Path::new("/app/src"));
+        assert_eq!(walk_root(Path::new("/app/{a,b}/*.tsx")), Path::new("/app"));
+        assert_eq!(walk_root(Path::new("/app/index.html")), Path::new("/app"));
+    }
+
+    #[test]
+    fn test_escape_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().to_owned();
+        fs::create_dir_all(base.join("app/[id]")).unwrap();
+        fs::write(base.join("app/[id]/page.tsx"), "").unwrap();
+        fs::write(base.join("app/i.tsx"), "").unwrap();
+
+        assert_eq!(escape_file("app/[id]/page.tsx", &base), "app/[[]id[]]/page.tsx");
+        assert_eq!(escape_file("app/*.tsx", &base), "app/*.tsx");
+
+        let matcher =
+            GlobMatcher::new([escape_file("app/[id]/page.tsx", &base)], base.clone()).unwrap();
+        let base = matcher.base().to_owned();
+        assert!(matcher.is_match(&base.join("app/[id]/page.tsx")));
+        assert!(!matcher.is_match(&base.join("app/i/page.tsx")));
+    }
+
+    #[test]
+    fn test_roots_outside_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let mono = dir.path().to_owned();
+        for dir in ["apps/web/src", "packages/ui/src", "packages/icons"] {
+            fs::create_dir_all(mono.join(dir)).unwrap();
+        }
+        for file in
+            ["apps/web/src/index.html", "packages/ui/src/button.tsx", "packages/icons/home.tsx"]
+        {
+            fs::write(mono.join(file), "").unwrap();
+        }
+        let mono = mono.canonicalize().unwrap();
+
+        let matcher = GlobMatcher::new(
+            [
+                "./src/**/*.html",
+                "./src/index.html",
+                "../../packages/ui/src/**/*.tsx",
+                mono.join("packages/*/*.tsx").to_str().unwrap(),
+            ],
+            mono.join("apps/web"),
+        )
+        .unwrap();
+        assert_eq!(matcher.roots(), [mono.join("apps/web/src"), mono.join("packages")]);
+
+        let files = walk(&matcher)
+            .into_iter()
+            .map(|p| p.strip_prefix(&mono).unwrap().to_str().unwrap().replace('\\', "/"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            files,
+            ["apps/web/src/index.html", "packages/icons/home.tsx", "packages/ui/src/button.tsx"]
+        );
+    }
+
+    #[test]
+    fn test_exclude_outside_base() {
+        // the default excludes are matched below the roots, a root may be inside `target`
+        let dir = tempfile::tempdir().unwrap();
+        let mono = dir.path().join("target/mono");
+        for file in [
+            "apps/web/index.html",
+            "packages/ui/src/button.tsx",
+            "packages/ui/node_modules/lib/b.tsx",
+            "packages/ui/dist/c.tsx",
+        ] {
+            fs::create_dir_all(mono.join(file).parent().unwrap()).unwrap();
+            fs::write(mono.join(file), "").unwrap();
+        }
+        let mono = mono.canonicalize().unwrap();
+
+        let matcher = GlobMatcher::with_exclude(
+            ["./**/*.html", "../../packages/ui/**/*.tsx"],
+            ["**/dist"],
+            mono.join("apps/web"),
+        )
+        .unwrap();
+        assert!(!matcher.is_match(&mono.join("packages/ui/node_modules/lib/b.tsx")));
+        assert!(matcher.is_excluded(&mono.join("packages/ui/dist"), true));
+
+        let files = walk(&matcher)
+            .into_iter()
+            .map(|p| p.strip_prefix(&mono).unwrap().to_str().unwrap().replace('\\', "/"))
+            .collect::<Vec<_>>();
+        assert_eq!(files, ["apps/web/index.html", "packages/ui/src/button.tsx"]);
+    }
+
+    #[test]
+    fn test_gitignore_outside_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let mono = dir.path().to_owned();
+        for file in ["apps/web/index.html", "packages/ui/button.tsx", "packages/ui/gen/icon.tsx"] {
+            fs::create_dir_all(mono.join(file).parent().unwrap()).unwrap();
+            fs::write(mono.join(file), "").unwrap();
+        }
+        fs::write(mono.join("packages/ui/.gitignore"), "gen/\n").unwrap();
+        let mono = mono.canonicalize().unwrap();
+
+        let matcher =
+            GlobMatcher::new(["../../packages/ui/**/*.tsx"], mono.join("apps/web")).unwrap();
+
+        // files of a walk root are filtered the same way when walked and when watched
+        assert!(!matcher.is_match(&mono.join("packages/ui/gen/icon.tsx")));
+        assert!(matcher.is_match(&mono.join("packages/ui/button.tsx")));
+        assert_eq!(walk(&matcher), [mono.join("packages/ui/button.tsx")]);
+    }
+}
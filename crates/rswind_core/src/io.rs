@@ -1,62 +1,136 @@
 use std::{
     convert::Infallible,
     fmt::Display,
-    fs::{read_to_string, OpenOptions},
-    io::Write,
+    fs,
+    io::{self, Write},
     path::{Path, PathBuf},
+    process,
     str::FromStr,
+    sync::Mutex,
 };
 
-use crate::generator::GeneratorInput;
+use crate::{generator::GeneratorInput, glob::GlobMatcher};
+use ignore::{WalkBuilder, WalkState};
 use rswind_extractor::Extractor;
-use walkdir::WalkDir;
+use thiserror::Error;
+use tracing::warn;
+
+#[derive(Debug, Error)]
+pub enum IoError {
+    #[error("Failed to read {0}: {1}")]
+    Read(PathBuf, io::Error),
+    #[error("{0} is not valid UTF-8")]
+    InvalidUtf8(PathBuf),
+    #[error("The path {} is not valid UTF-8", .0.display())]
+    InvalidPath(PathBuf),
+    #[error("Failed to write {0}: {1}")]
+    Write(PathBuf, io::Error),
+    #[error("Failed to write to stdout: {0}")]
+    Stdout(io::Error),
+}
 
 pub struct FileInput {
     pub(crate) content: String,
-    pub(crate) path: PathBuf,
+    pub(crate) path: String,
 }
 
 impl FileInput {
-    pub fn from_file(f: PathBuf) -> Self {
-        Self { content: read_to_string(&f).unwrap(), path: f }
+    pub fn new(path: impl Into<String>, content: impl Into<String>) -> Self {
+        Self { content: content.into(), path: path.into() }
+    }
+
+    /// Read a content file, callers usually skip the file with a warning on errors
+    pub fn from_file(f: PathBuf) -> Result<Self, IoError> {
+        let Some(path) = f.to_str().map(str::to_owned) else {
+            return Err(IoError::InvalidPath(f));
+        };
+        match fs::read_to_string(&f) {
+            Ok(content) => Ok(Self { content, path }),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => Err(IoError::InvalidUtf8(f)),
+            Err(e) => Err(IoError::Read(f, e)),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        Path::new(&self.path)
     }
 }
 
+/// Read a content file, a file that can not be read or is not UTF-8 is skipped with a warning
+pub fn read_content(path: PathBuf) -> Option<FileInput> {
+    FileInput::from_file(path).map_err(|e| warn!("{}, skipped", e)).ok()
+}
+
 impl<'a> From<&'a FileInput> for GeneratorInput<'a> {
     fn from(f: &'a FileInput) -> Self {
-        GeneratorInput::new(f.path.to_str().unwrap(), &f.content)
+        GeneratorInput::new(&f.path, &f.content)
     }
 }
 
 impl<'a> From<&'a FileInput> for Extractor<'a> {
     fn from(f: &'a FileInput) -> Self {
-        Extractor::new(&f.content, f.path.to_str().unwrap())
+        Extractor::new(&f.content, f.path.as_str())
     }
 }
 
-pub fn walk(base: impl AsRef<Path>) -> Vec<PathBuf> {
-    WalkDir::new(base)
-        .into_iter()
-        .filter_entry(|e| {
-            // TODO: Extract this
-            e.file_name() != "node_modules" && e.file_name() != ".git" && e.file_name() != "target"
+/// Walk the roots of `matcher` in parallel, returns the files it matches
+///
+/// `.gitignore` and `.ignore` files are honored, excluded directories are pruned without being read.
+pub fn walk(matcher: &GlobMatcher) -> Vec<PathBuf> {
+    let Some((first, rest)) = matcher.roots().split_first() else {
+        return Vec::new();
+    };
+
+    let files = Mutex::new(Vec::new());
+    let prune = matcher.clone();
+
+    let mut builder = WalkBuilder::new(first);
+    for root in rest {
+        builder.add(root);
+    }
+    builder
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(move |e| {
+            !(e.file_type().is_some_and(|t| t.is_dir()) && prune.is_excluded(e.path(), true))
         })
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.into_path().canonicalize().unwrap())
-        .collect()
+        .build_parallel()
+        .run(|| {
+            Box::new(|entry| {
+                if let Ok(entry) = entry {
+                    if entry.file_type().is_some_and(|t| t.is_file())
+                        && matcher.is_match(entry.path())
+                    {
+                        files.lock().unwrap().push(entry.into_path());
+                    }
+                }
+                WalkState::Continue
+            })
+        });
+
+    let mut files = files.into_inner().unwrap();
+    files.sort_unstable();
+    files
 }
 
-pub fn write_file(content: &str, filename: impl AsRef<Path>) {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .append(false)
-        .open(filename)
-        .unwrap()
-        .write_all(content.as_bytes())
-        .unwrap();
+/// Write `content` to a temporary file next to `filename` and rename it,
+/// so watchers never see a partially written file. Missing parent directories are created.
+pub fn write_file(content: &str, filename: impl AsRef<Path>) -> Result<(), IoError> {
+    let path = filename.as_ref();
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{name}.{}.tmp", process::id()));
+
+    let write = || {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&temp, content)?;
+        fs::rename(&temp, path)
+    };
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        IoError::Write(path.to_owned(), e)
+    })
 }
 
 #[derive(Debug, Clone)]
@@ -68,7 +142,7 @@ pub enum OutputChannel {
 impl Display for OutputChannel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            OutputChannel::Stdout => f.write_str("stdout"),
+            OutputChannel::Stdout => f.write_str("-"),
             OutputChannel::FileSystem(path) => f.write_str(&path.to_string_lossy()),
         }
     }
@@ -78,13 +152,69 @@ impl FromStr for OutputChannel {
     type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self::FileSystem(PathBuf::from(s)))
+        Ok(match s {
+            "-" => Self::Stdout,
+            _ => Self::FileSystem(PathBuf::from(s)),
+        })
     }
 }
 
-pub fn write_output(content: &str, output: &OutputChannel) {
+pub fn write_output(content: &str, output: &OutputChannel) -> Result<(), IoError> {
     match output {
-        OutputChannel::Stdout => std::io::stdout().write_all(content.as_bytes()).unwrap(),
+        OutputChannel::Stdout => {
+            io::stdout().write_all(content.as_bytes()).map_err(IoError::Stdout)
+        }
         OutputChannel::FileSystem(path) => write_file(content, path),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logo.png");
+        fs::write(&path, [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+
+        assert!(
+            matches!(FileInput::from_file(path.clone()), Err(IoError::InvalidUtf8(p)) if p == path)
+        );
+        assert!(read_content(path).is_none());
+        assert!(matches!(
+            FileInput::from_file(dir.path().join("missing.html")),
+            Err(IoError::Read(..))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_file_invalid_path() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(OsStr::from_bytes(b"index\xff.html"));
+        fs::write(&path, "<div class=\"flex\"></div>").unwrap();
+
+        assert!(
+            matches!(FileInput::from_file(path.clone()), Err(IoError::InvalidPath(p)) if p == path)
+        );
+        assert!(read_content(path).is_none());
+    }
+
+    #[test]
+    fn test_write_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dist/css/index.css");
+
+        write_file(".a {}", &path).unwrap();
+        write_file(".b {}", &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), ".b {}");
+        // no temporary file is left
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        let err = write_file(".a {}", dir.path().join("dist/css/index.css/nested.css"));
+        assert!(matches!(err, Err(IoError::Write(..))));
+    }
+}
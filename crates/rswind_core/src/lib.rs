@@ -2,13 +2,16 @@ pub mod cache;
 pub mod common;
 pub mod config;
 pub mod design;
+pub mod extractor;
 pub mod generator;
 pub mod glob;
 pub mod io;
 pub mod ordering;
 pub mod parse;
+pub mod plugin;
 pub mod process;
 pub mod processor;
+pub mod stylesheet;
 pub mod types;
 
 pub use config::GeneratorConfig;
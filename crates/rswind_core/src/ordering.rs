@@ -1,12 +1,19 @@
 use std::{hash::Hash, str::FromStr};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize)]
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize, Serialize,
+)]
 #[serde(rename_all = "kebab-case")]
 #[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "build", derive(instance_code::InstanceCode), instance(path = rswind_core::ordering))]
 pub enum OrderingKey {
+    /// Component like utilities from presets, e.g. `prose`, come before all other utilities
+    Component,
+    /// Modifiers of a component, e.g. `prose-invert`, override the component itself
+    ComponentModifier,
+
     Translate,
     TranslateAxis,
     Scale,
@@ -13,8 +13,8 @@ use crate::{
     common::MaybeArbitrary,
     ordering::OrderingKey,
     process::{
-        ComposableHandler, RawValueDef, RuleMatchingFn, ThemeParseError, Utility, UtilityGroup,
-        UtilityHandler, Variant, VariantHandlerExt,
+        ComposableHandler, RawValueDef, RuleMatchingFn, ThemeParseError, TryRuleMatchingFn,
+        Utility, UtilityGroup, UtilityHandler, Variant, VariantHandlerExt,
     },
     types::{CssProperty, CssTypeValidator},
 };
@@ -183,9 +183,13 @@ impl Debug for dyn AdditionalCssHandler {
 
 impl UtilityBuilder {
     pub fn new(key: impl Into<SmolStr>, handler: impl RuleMatchingFn + 'static) -> Self {
+        Self::with_handler(key, UtilityHandler::new(handler))
+    }
+
+    fn with_handler(key: impl Into<SmolStr>, handler: UtilityHandler) -> Self {
         Self {
             key: key.into(),
-            handler: Some(UtilityHandler::new(handler)),
+            handler: Some(handler),
             theme_key: None,
             supports_negative: false,
             supports_fraction: false,
@@ -198,11 +202,23 @@ impl UtilityBuilder {
         }
     }
 
+    /// Like [`UtilityBuilder::new`], the candidate is invalid when `handler` returns `None`
+    pub fn new_optional(
+        key: impl Into<SmolStr>,
+        handler: impl TryRuleMatchingFn + 'static,
+    ) -> Self {
+        Self::with_handler(key, UtilityHandler::new_optional(handler))
+    }
+
     pub fn parse(self, theme: &Theme) -> Result<(SmolStr, Utility), ThemeParseError> {
+        let Some(handler) = self.handler else {
+            return Err(ThemeParseError::MissingCss(self.key));
+        };
+
         Ok((
             self.key,
             Utility {
-                handler: self.handler.unwrap(),
+                handler,
                 supports_negative: self.supports_negative,
                 supports_fraction: self.supports_fraction,
                 value_def: RawValueDef { theme_key: self.theme_key, validator: self.validator }
@@ -0,0 +1,345 @@
+use std::{borrow::Cow, fmt::Debug, sync::Arc};
+
+use rustc_hash::FxHashMap as HashMap;
+use smol_str::SmolStr;
+use thiserror::Error;
+
+use crate::{config::GeneratorConfig, DesignSystem};
+
+/// A plugin that hooks into the whole generation lifecycle
+///
+/// Unlike [`LoadPreset`](crate::common::LoadPreset) and [`LoadTheme`](crate::common::LoadTheme),
+/// a plugin has a name, can declare dependencies and conflicts,
+/// and is able to transform candidates and the final generated css.
+///
+/// Hooks are called in the following order:
+/// 1. [`Plugin::configure`], before the user config is applied
+/// 2. [`Plugin::theme`], after the base theme is loaded and before user theme is merged
+/// 3. [`Plugin::utilities`] and [`Plugin::variants`], after all presets are loaded
+/// 4. [`Plugin::transform_candidate`], for every extracted candidate
+/// 5. [`Plugin::transform_css`], on the final generated css
+///
+/// All hooks are optional except [`Plugin::name`].
+///
+/// # Examples
+///
+/// ```
+/// use rswind_core::{plugin::Plugin, DesignSystem, Generator};
+/// use rswind_css::{Decl, DeclList};
+///
+/// struct Center;
+///
+/// impl Plugin for Center {
+///     fn name(&self) -> &str {
+///         "center"
+///     }
+///
+///     fn utilities(&self, design: &mut DesignSystem) {
+///         design.add_static("center", DeclList::from([Decl::new("place-items", "center")]));
+///     }
+/// }
+///
+/// let mut processor = Generator::builder().with_plugin(Center).build_processor().unwrap();
+///
+/// assert_eq!(processor.run_with(["center"]).css.as_str(), ".center {\n  place-items: center;\n}\n");
+/// ```
+pub trait Plugin: Send + Sync {
+    /// The unique name of the plugin
+    fn name(&self) -> &str;
+
+    /// Names of plugins which must be loaded before this one
+    fn dependencies(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Names of plugins which can not be loaded together with this one
+    fn conflicts(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Modify the user config before it's applied
+    fn configure(&self, _config: &mut GeneratorConfig) {}
+
+    /// Add or modify theme values
+    fn theme(&self, _design: &mut DesignSystem) {}
+
+    /// Add utilities
+    fn utilities(&self, _design: &mut DesignSystem) {}
+
+    /// Add variants
+    fn variants(&self, _design: &mut DesignSystem) {}
+
+    /// Transform an extracted candidate before it's generated
+    ///
+    /// Returns `None` to keep the candidate unchanged
+    fn transform_candidate(&self, _candidate: &str) -> Option<SmolStr> {
+        None
+    }
+
+    /// Transform the final generated css
+    fn transform_css(&self, _css: &mut String) {}
+}
+
+impl Debug for dyn Plugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Plugin").field("name", &self.name()).finish()
+    }
+}
+
+/// Create a plugin from its options in config `plugins`,
+/// options will be [`serde_json::Value::Null`] if not provided
+pub type PluginFactory = fn(&serde_json::Value) -> Result<Box<dyn Plugin>, PluginError>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PluginError {
+    #[error("Unknown plugin `{0}`")]
+    Unknown(SmolStr),
+    #[error("Invalid options for plugin `{plugin}`: {message}")]
+    InvalidOptions { plugin: SmolStr, message: String },
+    #[error("Plugin `{0}` is registered more than once")]
+    Duplicate(SmolStr),
+    #[error("Plugin `{plugin}` depends on `{dependency}`, which is not registered")]
+    MissingDependency { plugin: SmolStr, dependency: SmolStr },
+    #[error("Plugin `{0}` conflicts with `{1}`")]
+    Conflict(SmolStr, SmolStr),
+    #[error("Circular dependency between plugins: {0}")]
+    Cycle(SmolStr),
+}
+
+/// A registry of plugins, used by [`GeneratorBuilder`](crate::generator::GeneratorBuilder)
+///
+/// Plugins are loaded in registration order, unless a plugin depends on
+/// a plugin registered later.
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Box<dyn Plugin>>,
+    resolved: bool,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, plugin: impl Plugin + 'static) -> &mut Self {
+        self.plugins.push(Box::new(plugin));
+        self.resolved = false;
+        self
+    }
+
+    pub fn add_boxed(&mut self, plugin: Box<dyn Plugin>) -> &mut Self {
+        self.plugins.push(plugin);
+        self.resolved = false;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Plugin> {
+        self.plugins.iter().map(AsRef::as_ref)
+    }
+
+    /// Check duplicates, missing dependencies and conflicts,
+    /// then sort the plugins so that every plugin comes after its dependencies.
+    pub fn resolve(&mut self) -> Result<(), PluginError> {
+        if self.resolved {
+            return Ok(());
+        }
+
+        let mut index = HashMap::default();
+        for (i, plugin) in self.plugins.iter().enumerate() {
+            if index.insert(plugin.name(), i).is_some() {
+                return Err(PluginError::Duplicate(plugin.name().into()));
+            }
+        }
+
+        for plugin in self.plugins.iter() {
+            for dependency in plugin.dependencies() {
+                if !index.contains_key(dependency) {
+                    return Err(PluginError::MissingDependency {
+                        plugin: plugin.name().into(),
+                        dependency: (*dependency).into(),
+                    });
+                }
+            }
+            for conflict in plugin.conflicts() {
+                if index.contains_key(conflict) {
+                    return Err(PluginError::Conflict(plugin.name().into(), (*conflict).into()));
+                }
+            }
+        }
+
+        // depth-first topological sort, keeps registration order when possible
+        let mut state = vec![VisitState::Unvisited; self.plugins.len()];
+        let mut order = Vec::with_capacity(self.plugins.len());
+
+        for i in 0..self.plugins.len() {
+            visit(i, &self.plugins, &index, &mut state, &mut order)?;
+        }
+
+        let mut plugins = self.plugins.drain(..).map(Some).collect::<Vec<_>>();
+        self.plugins = order.into_iter().filter_map(|i| plugins[i].take()).collect();
+        self.resolved = true;
+
+        Ok(())
+    }
+
+    pub fn into_list(self) -> PluginList {
+        PluginList(self.plugins.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    Unvisited,
+    Visiting,
+    Visited,
+}
+
+fn visit(
+    i: usize,
+    plugins: &[Box<dyn Plugin>],
+    index: &HashMap<&str, usize>,
+    state: &mut [VisitState],
+    order: &mut Vec<usize>,
+) -> Result<(), PluginError> {
+    match state[i] {
+        VisitState::Visited => return Ok(()),
+        VisitState::Visiting => return Err(PluginError::Cycle(plugins[i].name().into())),
+        VisitState::Unvisited => {}
+    }
+
+    state[i] = VisitState::Visiting;
+    for dependency in plugins[i].dependencies() {
+        visit(index[dependency], plugins, index, state, order)?;
+    }
+    state[i] = VisitState::Visited;
+    order.push(i);
+
+    Ok(())
+}
+
+/// Resolved plugins, shared by [`GeneratorProcessor`](crate::processor::GeneratorProcessor)
+/// to run transform hooks during generation.
+#[derive(Debug, Clone)]
+pub struct PluginList(Arc<[Box<dyn Plugin>]>);
+
+impl Default for PluginList {
+    fn default() -> Self {
+        Self(Vec::new().into())
+    }
+}
+
+impl PluginList {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Plugin> {
+        self.0.iter().map(AsRef::as_ref)
+    }
+
+    pub fn transform_candidate<'a>(&self, candidate: &'a str) -> Cow<'a, str> {
+        self.0.iter().fold(Cow::Borrowed(candidate), |acc, plugin| {
+            match plugin.transform_candidate(&acc) {
+                Some(transformed) => Cow::Owned(transformed.into()),
+                None => acc,
+            }
+        })
+    }
+
+    pub fn transform_css(&self, css: &mut String) {
+        for plugin in self.0.iter() {
+            plugin.transform_css(css);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Named(&'static str, &'static [&'static str], &'static [&'static str]);
+
+    impl Plugin for Named {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn dependencies(&self) -> &[&str] {
+            self.1
+        }
+
+        fn conflicts(&self) -> &[&str] {
+            self.2
+        }
+    }
+
+    fn names(registry: &PluginRegistry) -> Vec<&str> {
+        registry.iter().map(|p| p.name()).collect()
+    }
+
+    #[test]
+    fn test_resolve_order() {
+        let mut registry = PluginRegistry::new();
+        registry.add(Named("forms", &["base"], &[])).add(Named("typography", &[], &[])).add(Named(
+            "base",
+            &[],
+            &[],
+        ));
+
+        registry.resolve().unwrap();
+
+        assert_eq!(names(&registry), ["base", "forms", "typography"]);
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let mut registry = PluginRegistry::new();
+        registry.add(Named("a", &[], &[])).add(Named("a", &[], &[]));
+        assert_eq!(registry.resolve(), Err(PluginError::Duplicate("a".into())));
+
+        let mut registry = PluginRegistry::new();
+        registry.add(Named("a", &["b"], &[]));
+        assert_eq!(
+            registry.resolve(),
+            Err(PluginError::MissingDependency { plugin: "a".into(), dependency: "b".into() })
+        );
+
+        let mut registry = PluginRegistry::new();
+        registry.add(Named("a", &[], &["b"])).add(Named("b", &[], &[]));
+        assert_eq!(registry.resolve(), Err(PluginError::Conflict("a".into(), "b".into())));
+
+        let mut registry = PluginRegistry::new();
+        registry.add(Named("a", &["b"], &[])).add(Named("b", &["a"], &[]));
+        assert_eq!(registry.resolve(), Err(PluginError::Cycle("a".into())));
+    }
+
+    #[test]
+    fn test_transform_candidate() {
+        struct StripPrefix;
+
+        impl Plugin for StripPrefix {
+            fn name(&self) -> &str {
+                "strip-prefix"
+            }
+
+            fn transform_candidate(&self, candidate: &str) -> Option<SmolStr> {
+                candidate.strip_prefix("x-").map(Into::into)
+            }
+        }
+
+        let mut registry = PluginRegistry::new();
+        registry.add(StripPrefix);
+        let list = registry.into_list();
+
+        assert_eq!(list.transform_candidate("x-flex"), "flex");
+        assert!(matches!(list.transform_candidate("flex"), Cow::Borrowed("flex")));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod theme_function;
 pub mod utility;
 pub mod variant;
 
@@ -85,6 +86,8 @@ pub struct RawValueDef {
 pub enum ThemeParseError {
     #[error("Theme key `{0}` does not exist")]
     InvalidThemeKey(SmolStr),
+    #[error("Utility `{0}` has no `css`")]
+    MissingCss(SmolStr),
 }
 
 impl RawValueDef {
@@ -0,0 +1,209 @@
+//! `theme()` and `--theme()` functions in arbitrary values and input stylesheets
+
+use std::borrow::Cow;
+
+use rswind_theme::Theme;
+use smol_str::SmolStr;
+use thiserror::Error;
+
+use crate::common::as_color;
+
+/// Namespaces of theme variables like `--color-blue-500` and the theme keys they map to,
+/// longer prefixes first
+static NAMESPACES: &[(&str, &str)] = &[
+    ("--color", "colors"),
+    ("--spacing", "spacing"),
+    ("--font-weight", "fontWeight"),
+    ("--font", "fontFamily"),
+    ("--text", "fontSize"),
+    ("--tracking", "letterSpacing"),
+    ("--leading", "lineHeight"),
+    ("--breakpoint", "screens"),
+    ("--radius", "borderRadius"),
+    ("--shadow", "boxShadow"),
+    ("--blur", "blur"),
+    ("--opacity", "opacity"),
+];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ThemeFunctionError {
+    #[error("Unknown theme value: {0}")]
+    UnknownValue(SmolStr),
+    #[error("Unclosed theme function: {0}")]
+    Unclosed(SmolStr),
+}
+
+/// Replace `theme(colors.blue.500 / 50%)` and `--theme(--color-blue-500)` calls in `input`
+/// with their theme values, `input` is borrowed if it has none
+pub fn resolve_theme_functions<'a>(
+    input: &'a str,
+    theme: &Theme,
+) -> Result<Cow<'a, str>, ThemeFunctionError> {
+    let bytes = input.as_bytes();
+    let mut res = String::new();
+    let mut flushed = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if input[i..].starts_with("/*") {
+            i = input[i + 2..].find("*/").map_or(bytes.len(), |n| i + 2 + n + 2);
+            continue;
+        }
+        if let quote @ (b'"' | b'\'') = bytes[i] {
+            i = skip_string(bytes, i, quote);
+            continue;
+        }
+
+        let name_len = match &input[i..] {
+            rest if rest.starts_with("--theme(") => "--theme(".len(),
+            rest if rest.starts_with("theme(") => "theme(".len(),
+            _ => 0,
+        };
+        let is_call = name_len > 0
+            && (i == 0 || !(bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'-'));
+        if !is_call {
+            i += 1;
+            continue;
+        }
+
+        let args_start = i + name_len;
+        let args_end = find_call_close(bytes, args_start)
+            .ok_or_else(|| ThemeFunctionError::Unclosed(input[i..].into()))?;
+        let value = resolve_theme_value(&input[args_start..args_end], theme)
+            .ok_or_else(|| ThemeFunctionError::UnknownValue(input[i..=args_end].into()))?;
+
+        res.push_str(&input[flushed..i]);
+        res.push_str(&value);
+        i = args_end + 1;
+        flushed = i;
+    }
+
+    if flushed == 0 {
+        return Ok(Cow::Borrowed(input));
+    }
+    res.push_str(&input[flushed..]);
+    Ok(Cow::Owned(res))
+}
+
+/// Resolve the argument of a theme function, e.g. `colors.blue.500 / 50%`, `'spacing[2.5]'`
+/// or `--color-blue-500`, a trailing opacity mixes the color with `transparent`
+pub fn resolve_theme_value(arg: &str, theme: &Theme) -> Option<SmolStr> {
+    let path = trim(arg);
+    if let Some(value) = resolve_path(path, theme) {
+        return Some(value);
+    }
+
+    // e.g. `spacing.1/2` is a path, only split when the whole path is unknown
+    let (path, opacity) = arg.rsplit_once('/')?;
+    let value = resolve_path(trim(path), theme)?;
+    let opacity = trim(opacity);
+    let alpha = match opacity.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+        // `0.5` or `50`
+        None => match opacity.parse::<f32>() {
+            Ok(alpha) if alpha > 1.0 => alpha / 100.0,
+            Ok(alpha) => alpha,
+            Err(_) => theme.get_value("opacity", opacity)?.parse().ok()?,
+        },
+    };
+    Some(as_color(&value, Some(&alpha.to_string())))
+}
+
+/// Trim whitespace, `_` which is a space in arbitrary values, and quotes
+fn trim(s: &str) -> &str {
+    s.trim_matches(|c: char| c.is_whitespace() || c == '_').trim_matches(['"', '\''])
+}
+
+fn resolve_path(path: &str, theme: &Theme) -> Option<SmolStr> {
+    if !path.starts_with("--") {
+        return theme.resolve(path);
+    }
+
+    NAMESPACES.iter().find_map(|(prefix, key)| match path.strip_prefix(prefix)? {
+        "" => theme.resolve(key),
+        rest => theme.get_value(key, rest.strip_prefix('-')?),
+    })
+}
+
+/// The position of the `)` closing a call whose arguments start at `start`
+fn find_call_close(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                i = skip_string(bytes, i, quote);
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' if depth == 0 => return Some(i),
+            b')' => depth -= 1,
+            _ => (),
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The position after the string starting at `start`, escaped quotes are skipped
+pub(crate) fn skip_string(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() && bytes[i] != quote {
+        if bytes[i] == b'\\' {
+            i += 1;
+        }
+        i += 1;
+    }
+    (i + 1).min(bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rswind_theme::ThemeMap;
+    use rustc_hash::FxHashMap as HashMap;
+
+    use super::*;
+
+    fn theme() -> Theme {
+        let map = |values: &[(&str, &str)]| {
+            Arc::new(ThemeMap::Dynamic(
+                values.iter().map(|(k, v)| (SmolStr::from(*k), SmolStr::from(*v))).collect(),
+            ))
+        };
+        let mut theme = HashMap::default();
+        theme.insert("colors".into(), map(&[("blue-500", "#3b82f6")]));
+        theme.insert("spacing".into(), map(&[("4", "1rem"), ("2.5", "0.625rem"), ("1/2", "50%")]));
+        theme.insert("opacity".into(), map(&[("50", "0.5")]));
+        theme.insert("borderRadius".into(), map(&[("DEFAULT", "0.25rem"), ("lg", "0.5rem")]));
+        Theme(theme)
+    }
+
+    #[test]
+    fn test_resolve_theme_functions() {
+        let theme = theme();
+        let resolve = |input| resolve_theme_functions(input, &theme);
+
+        assert_eq!(resolve("calc(theme(spacing.4)+2px)").unwrap(), "calc(1rem+2px)");
+        assert_eq!(resolve("theme('spacing[2.5]') theme(spacing.1/2)").unwrap(), "0.625rem 50%");
+        assert_eq!(resolve("theme(borderRadius) --theme(--radius-lg)").unwrap(), "0.25rem 0.5rem");
+        assert_eq!(
+            resolve("theme(colors.blue.500 / 50%)").unwrap(),
+            "color-mix(in srgb, #3b82f6 50%, transparent)"
+        );
+        assert_eq!(
+            resolve("--theme(--color-blue-500/50)").unwrap(),
+            "color-mix(in srgb, #3b82f6 50%, transparent)"
+        );
+        assert!(matches!(resolve("1px solid"), Ok(Cow::Borrowed("1px solid"))));
+        assert_eq!(
+            resolve(r#"content: "theme(nope)" theme('spacing.4') 'a\'theme(x)'"#).unwrap(),
+            r#"content: "theme(nope)" 1rem 'a\'theme(x)'"#
+        );
+        assert_eq!(
+            resolve("theme(colors.nope)").unwrap_err(),
+            ThemeFunctionError::UnknownValue("theme(colors.nope)".into())
+        );
+        assert!(matches!(resolve("theme(spacing.4"), Err(ThemeFunctionError::Unclosed(_))));
+    }
+}
@@ -17,8 +17,13 @@ pub trait RuleMatchingFn: Fn(MetaData, SmolStr) -> Rule + Send + Sync + 'static
 
 impl<T: Fn(MetaData, SmolStr) -> Rule + Send + Sync + 'static> RuleMatchingFn for T {}
 
+/// Like [`RuleMatchingFn`], but returns `None` if the value doesn't match
+pub trait TryRuleMatchingFn: Fn(MetaData, SmolStr) -> Option<Rule> + Send + Sync + 'static {}
+
+impl<T: Fn(MetaData, SmolStr) -> Option<Rule> + Send + Sync + 'static> TryRuleMatchingFn for T {}
+
 #[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
-pub struct UtilityHandler(pub Box<dyn RuleMatchingFn>);
+pub struct UtilityHandler(pub Box<dyn TryRuleMatchingFn>);
 
 impl Debug for UtilityHandler {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -29,10 +34,15 @@ impl Debug for UtilityHandler {
 
 impl UtilityHandler {
     pub fn new(handler: impl RuleMatchingFn + 'static) -> Self {
+        Self(Box::new(move |meta, value| Some(handler(meta, value))))
+    }
+
+    /// A handler which may not match every value, the candidate is invalid when it returns `None`
+    pub fn new_optional(handler: impl TryRuleMatchingFn + 'static) -> Self {
         Self(Box::new(handler))
     }
 
-    pub fn call(&self, meta: MetaData, value: SmolStr) -> Rule {
+    pub fn call(&self, meta: MetaData, value: SmolStr) -> Option<Rule> {
         (self.0)(meta, value)
     }
 }
@@ -130,7 +140,7 @@ pub struct UtilityApplyResult {
 impl Utility {
     pub fn new<F: RuleMatchingFn + 'static>(handler: F) -> Self {
         Self {
-            handler: UtilityHandler(Box::new(handler)),
+            handler: UtilityHandler::new(handler),
             supports_negative: false,
             supports_fraction: false,
             value_def: ValueDef::default(),
@@ -179,7 +189,7 @@ impl Utility {
             css = extra_css.handle(candidate.value.unwrap_or_default().as_str().into());
         }
 
-        let mut node = self.handler.call(meta, process_result);
+        let mut node = self.handler.call(meta, process_result)?;
 
         if let Some(selector) = &self.selector {
             node.selector.clone_from(selector);
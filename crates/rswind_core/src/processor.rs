@@ -11,6 +11,7 @@ use crate::{
     cache::{Cache, CacheState, GeneratorCache},
     design::{CacheKey, DesignSystem, GeneratedUtility},
     generator::GeneratorBuilder,
+    plugin::{PluginList, PluginRegistry},
     process::build_group_selector,
 };
 
@@ -18,6 +19,7 @@ pub struct GeneratorProcessor {
     pub design: Arc<DesignSystem>,
     pub cache: GeneratorCache,
     pub options: GenOptions,
+    pub plugins: PluginList,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -46,6 +48,10 @@ impl GeneratorProcessor {
         GeneratorBuilder {
             presets: Vec::new(),
             themes: Vec::new(),
+            plugins: PluginRegistry::default(),
+            plugin_factories: HashMap::default(),
+            plugins_configured: false,
+            extractors: Vec::new(),
             config: None,
             design: DesignSystem::default(),
             options: GenOptions::default(),
@@ -59,48 +65,94 @@ impl GeneratorProcessor {
         I: IntoIterator,
         I::Item: AsRef<str>,
     {
-        let res = input
+        let res = self.generate_sequential(input);
+        self.generate_css(res)
+    }
+
+    /// Generate utilities of unseen candidates without writing css,
+    /// invalid candidates are marked in the cache
+    pub fn generate_sequential<I>(&mut self, input: I) -> GenResultList
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        input
             .into_iter()
             .filter_map(|s| {
-                let s = s.as_ref();
-                if self.cache.has_seen(s) {
+                let s = self.plugins.transform_candidate(s.as_ref());
+                if self.cache.has_seen(&s) {
                     return None;
                 }
-                let res = self.design.generate(s);
+                let res = self.design.generate(&s);
                 if res.is_none() {
-                    self.cache.mark_invalid(SmolStr::from(s));
+                    self.cache.mark_invalid(SmolStr::from(s.as_ref()));
                 }
                 res
             })
-            .collect::<GenResultList>();
+            .collect()
+    }
 
+    pub fn run_parallel_with<I>(&mut self, input: I) -> GenerateResult
+    where
+        I: IntoParallelIterator,
+        I::Item: AsRef<str>,
+    {
+        let res = self.generate_parallel(input);
         self.generate_css(res)
     }
 
-    pub fn run_parallel_with<I>(&mut self, input: I) -> GenerateResult
+    /// Generate utilities of unseen candidates in parallel without writing css,
+    /// invalid candidates are marked in the cache
+    pub fn generate_parallel<I>(&mut self, input: I) -> GenResultList
     where
         I: IntoParallelIterator,
         I::Item: AsRef<str>,
     {
-        let (invalid, valid): (Vec<_>, Vec<_>) =
-            input.into_par_iter().filter(|s| !self.cache.has_seen(s.as_ref())).partition_map(|s| {
-                let s = s.as_ref();
-                match self.design.generate(s) {
+        let (invalid, valid): (Vec<_>, Vec<_>) = input
+            .into_par_iter()
+            .filter_map(|s| {
+                let s = self.plugins.transform_candidate(s.as_ref());
+                (!self.cache.has_seen(&s)).then(|| match self.design.generate(&s) {
                     Some(r) => Right(r),
-                    None => Left(SmolStr::from(s)),
-                }
-            });
+                    None => Left(SmolStr::from(s.as_ref())),
+                })
+            })
+            .partition_map(|r| r);
 
         self.cache.mark_invalid_many(invalid);
 
-        self.generate_css(valid)
+        valid
+    }
+
+    /// Generate in parallel if [`GenOptions::parallel`] is enabled, otherwise sequentially
+    pub fn run<I, T>(&mut self, input: I) -> GenerateResult
+    where
+        I: IntoIterator<Item = T> + IntoParallelIterator<Item = T>,
+        T: AsRef<str> + Send,
+    {
+        let res = self.generate(input);
+        self.generate_css(res)
+    }
+
+    /// Generate utilities without writing css like [`Self::run`], e.g. to inspect them
+    /// before [`Self::generate_css`]
+    pub fn generate<I, T>(&mut self, input: I) -> GenResultList
+    where
+        I: IntoIterator<Item = T> + IntoParallelIterator<Item = T>,
+        T: AsRef<str> + Send,
+    {
+        match self.options.parallel {
+            true => self.generate_parallel(input),
+            false => self.generate_sequential(input),
+        }
     }
 
     pub fn generate_css(&mut self, mut res: GenResultList) -> GenerateResult {
         let len = res.len();
         info!("{} new utilities generated", len);
 
-        if res.is_empty() {
+        // Base styles need to be written once even if there are no utilities
+        if res.is_empty() && (self.design.base.is_empty() || self.cache.state.is_cached()) {
             return GenerateResult { css: self.cache.css(), kind: ResultKind::Cached };
         }
 
@@ -112,6 +164,7 @@ impl GeneratorProcessor {
         }
 
         let mut writer = Writer::new(String::with_capacity(1024));
+        let _ = self.design.base.to_css(&mut writer);
         process_result(res, &mut self.cache, &mut writer);
 
         // During the first run and one shot run, grouped css and additional css will be written here
@@ -128,7 +181,10 @@ impl GeneratorProcessor {
 
         self.cache.state.mark_cached();
 
-        let css = Arc::new(writer.dest);
+        let mut css = writer.dest;
+        self.plugins.transform_css(&mut css);
+
+        let css = Arc::new(css);
         self.cache.store_css(css.clone());
 
         GenerateResult { css, kind: ResultKind::Generated }
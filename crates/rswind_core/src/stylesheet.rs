@@ -0,0 +1,436 @@
+//! Input stylesheets, generated CSS is inserted at `@tailwind` and `@import "rswind"` markers
+
+use std::mem;
+
+use cssparser::serialize_name;
+use rswind_css::{Rule, ToCssString};
+use smol_str::SmolStr;
+use thiserror::Error;
+
+use crate::{
+    design::DesignSystem,
+    process::theme_function::{resolve_theme_functions, skip_string, ThemeFunctionError},
+};
+
+/// A section of the generated CSS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// Base styles, e.g. preflight
+    Base,
+    /// Component classes, only defined with `@layer components` in the stylesheet
+    Components,
+    /// Generated utilities
+    Utilities,
+}
+
+impl Layer {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "base" => Some(Self::Base),
+            "components" => Some(Self::Components),
+            "utilities" => Some(Self::Utilities),
+            _ => None,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StylesheetError {
+    #[error("`{candidate}` in `@apply` of `{selector}` is not a valid utility")]
+    UnknownUtility { candidate: String, selector: String },
+    #[error("`@apply {0}` must be used inside a rule")]
+    ApplyOutsideRule(String),
+    #[error(transparent)]
+    ThemeFunction(#[from] ThemeFunctionError),
+}
+
+#[derive(Debug, Clone)]
+enum Chunk {
+    Css(String),
+    Marker(Layer),
+}
+
+/// A processed input stylesheet, e.g. `rswind -i src/app.css`
+///
+/// `@apply` and `theme()` are resolved when parsing, and `@layer base`, `@layer components`
+/// and `@layer utilities` blocks are moved to the marker of their layer, or to the end if it has none.
+///
+/// ```css
+/// @tailwind base;
+/// @tailwind components;
+/// @tailwind utilities;
+///
+/// @layer components {
+///   .btn {
+///     @apply px-4 py-2 hover:bg-blue-700;
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Stylesheet {
+    chunks: Vec<Chunk>,
+    layers: [String; 3],
+}
+
+impl Stylesheet {
+    pub fn parse(input: &str, design: &DesignSystem) -> Result<Self, StylesheetError> {
+        let input = resolve_theme_functions(input, &design.theme)?;
+
+        let mut parser = Parser {
+            input: &input,
+            pos: 0,
+            flushed: 0,
+            design,
+            out: String::with_capacity(input.len()),
+            chunks: Vec::new(),
+            layers: Default::default(),
+            in_layer: false,
+        };
+        parser.parse_block(None, &mut String::new())?;
+
+        let mut chunks = parser.chunks;
+        chunks.push(Chunk::Css(parser.out));
+        Ok(Self { chunks, layers: parser.layers })
+    }
+
+    /// Whether the stylesheet contains a marker of `layer`
+    pub fn has_marker(&self, layer: Layer) -> bool {
+        self.chunks.iter().any(|c| matches!(c, Chunk::Marker(l) if *l == layer))
+    }
+
+    /// Insert generated `css`, which starts with the base styles of `design`, at the markers
+    pub fn render(&self, design: &DesignSystem, css: &str) -> String {
+        let base = design.base.to_css_string();
+        // base styles are always written first, unless a plugin transformed the output
+        let (base, utilities) = match css.strip_prefix(base.as_str()) {
+            Some(utilities) => (base.as_str(), utilities),
+            None => ("", css),
+        };
+
+        let mut res =
+            String::with_capacity(css.len() + self.layers.iter().map(String::len).sum::<usize>());
+        for chunk in &self.chunks {
+            match chunk {
+                Chunk::Css(css) => res.push_str(css),
+                Chunk::Marker(layer) => {
+                    match layer {
+                        Layer::Base => res.push_str(base),
+                        Layer::Components => (),
+                        Layer::Utilities => res.push_str(utilities),
+                    }
+                    res.push_str(&self.layers[layer.index()]);
+                }
+            }
+        }
+
+        for layer in [Layer::Base, Layer::Components, Layer::Utilities] {
+            if !self.has_marker(layer) {
+                res.push_str(&self.layers[layer.index()]);
+            }
+        }
+        res
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Input before this position has been written
+    flushed: usize,
+    design: &'a DesignSystem,
+    out: String,
+    chunks: Vec<Chunk>,
+    layers: [String; 3],
+    in_layer: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn flush(&mut self, to: usize) {
+        self.out.push_str(&self.input[self.flushed..to]);
+        self.flushed = to;
+    }
+
+    fn skip_to(&mut self, pos: usize) {
+        self.pos = pos;
+        self.flushed = pos;
+    }
+
+    fn marker(&mut self, layers: &[Layer], end: usize) {
+        self.flush(self.pos);
+        self.chunks.push(Chunk::Css(mem::take(&mut self.out)));
+        self.chunks.extend(layers.iter().map(|l| Chunk::Marker(*l)));
+        self.skip_to(end);
+    }
+
+    /// Parse until the `}` closing this block, which is consumed but not written,
+    /// rules generated by `@apply` for variants are pushed to `pending`
+    fn parse_block(
+        &mut self,
+        selector: Option<&str>,
+        pending: &mut String,
+    ) -> Result<(), StylesheetError> {
+        let input = self.input;
+        let bytes = input.as_bytes();
+        let mut stmt_start = self.pos;
+
+        while self.pos < bytes.len() {
+            match bytes[self.pos] {
+                b'/' if bytes.get(self.pos + 1) == Some(&b'*') => {
+                    self.pos = input[self.pos + 2..]
+                        .find("*/")
+                        .map_or(bytes.len(), |i| self.pos + 2 + i + 2);
+                    stmt_start = self.pos;
+                }
+                quote @ (b'"' | b'\'') => self.pos = skip_string(bytes, self.pos, quote),
+                b'@' if input[stmt_start..self.pos].trim().is_empty() => {
+                    self.parse_at_rule(selector, pending)?;
+                    stmt_start = self.pos;
+                }
+                b'{' => {
+                    let rule_selector = input[stmt_start..self.pos].trim();
+                    let selector_start = self.pos - input[stmt_start..self.pos].trim_start().len();
+                    let indent = line_indent(input, selector_start);
+                    self.pos += 1;
+                    self.flush(self.pos);
+
+                    let mut rule_pending = String::new();
+                    self.parse_block(Some(rule_selector), &mut rule_pending)?;
+                    self.out.push('}');
+                    for line in rule_pending.lines() {
+                        self.out.push('\n');
+                        self.out.push_str(indent);
+                        self.out.push_str(line);
+                    }
+                    stmt_start = self.pos;
+                }
+                b'}' => {
+                    self.flush(self.pos);
+                    self.skip_to(self.pos + 1);
+                    return Ok(());
+                }
+                b';' => {
+                    self.pos += 1;
+                    stmt_start = self.pos;
+                }
+                _ => self.pos += 1,
+            }
+        }
+
+        self.flush(bytes.len());
+        Ok(())
+    }
+
+    /// Parse an at-rule starting at `@`
+    fn parse_at_rule(
+        &mut self,
+        selector: Option<&str>,
+        pending: &mut String,
+    ) -> Result<(), StylesheetError> {
+        let input = self.input;
+        let bytes = input.as_bytes();
+        let start = self.pos;
+        let name_end = start
+            + 1
+            + bytes[start + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == b'-')
+                .count();
+        let name = &input[start + 1..name_end];
+        let prelude_end = find_prelude_end(bytes, name_end);
+        let prelude = input[name_end..prelude_end].trim();
+        let terminator = bytes.get(prelude_end).copied();
+        let top_level = selector.is_none() && !self.in_layer;
+
+        match (name, terminator) {
+            ("tailwind", Some(b';') | None) if top_level => {
+                if let Some(layer) = Layer::parse(prelude) {
+                    self.marker(&[layer], (prelude_end + 1).min(bytes.len()));
+                    return Ok(());
+                }
+            }
+            ("import", Some(b';') | None)
+                if top_level && matches!(prelude, "\"rswind\"" | "'rswind'") =>
+            {
+                self.marker(
+                    &[Layer::Base, Layer::Components, Layer::Utilities],
+                    (prelude_end + 1).min(bytes.len()),
+                );
+                return Ok(());
+            }
+            ("apply", _) => {
+                let Some(selector) = selector else {
+                    return Err(StylesheetError::ApplyOutsideRule(prelude.to_owned()));
+                };
+                let decls = self.apply(prelude, selector, line_indent(input, start), pending)?;
+                self.flush(start);
+                self.out.push_str(&decls);
+                let end = match terminator {
+                    Some(b';') => prelude_end + 1,
+                    _ => prelude_end,
+                };
+                self.skip_to(end);
+                return Ok(());
+            }
+            ("layer", Some(b'{')) if top_level => {
+                if let Some(layer) = Layer::parse(prelude) {
+                    self.flush(start);
+                    let out = mem::take(&mut self.out);
+                    self.in_layer = true;
+                    self.skip_to(prelude_end + 1);
+                    self.parse_block(None, &mut String::new())?;
+                    self.in_layer = false;
+
+                    let body = mem::replace(&mut self.out, out);
+                    self.layers[layer.index()].push_str(&dedent(&body));
+                    // drop the line of the removed block
+                    self.out.truncate(self.out.trim_end_matches([' ', '\t']).len());
+                    if bytes.get(self.pos) == Some(&b'\n') {
+                        self.skip_to(self.pos + 1);
+                    }
+                    return Ok(());
+                }
+            }
+            _ => (),
+        }
+
+        match terminator {
+            // `@media`, `@supports`, etc. keep the selector of the enclosing rule
+            Some(b'{') => {
+                self.pos = prelude_end + 1;
+                self.flush(self.pos);
+                self.parse_block(selector, pending)?;
+                self.out.push('}');
+            }
+            Some(b';') => self.pos = prelude_end + 1,
+            _ => self.pos = prelude_end,
+        }
+        Ok(())
+    }
+
+    /// Expand `@apply` candidates to declarations, other rules like variants are pushed to `pending`
+    fn apply(
+        &self,
+        candidates: &str,
+        selector: &str,
+        indent: &str,
+        pending: &mut String,
+    ) -> Result<String, StylesheetError> {
+        let (candidates, important) = match candidates.strip_suffix("!important") {
+            Some(candidates) => (candidates, " !important"),
+            None => (candidates, ""),
+        };
+
+        let mut decls = Vec::new();
+        for candidate in candidates.split_whitespace() {
+            let unknown = || StylesheetError::UnknownUtility {
+                candidate: candidate.to_owned(),
+                selector: selector.to_owned(),
+            };
+            let generated = self.design.generate(candidate).ok_or_else(unknown)?;
+            let class = class_selector(candidate).ok_or_else(unknown)?;
+
+            for mut rule in generated.rule {
+                if rule.selector == class {
+                    decls.append(&mut rule.decls);
+                    if rule.rules.is_empty() {
+                        continue;
+                    }
+                }
+                replace_selector(&mut rule, &class, selector);
+                if !important.is_empty() {
+                    make_important(&mut rule);
+                }
+                pending.push_str(&rule.to_css_string());
+            }
+            if let Some(extra) = generated.extra_css {
+                pending.push_str(&extra.to_css_string());
+            }
+        }
+
+        Ok(decls
+            .iter()
+            .map(|d| format!("{}: {}{};", d.name, d.value, important))
+            .collect::<Vec<_>>()
+            .join(&format!("\n{indent}")))
+    }
+}
+
+/// The indentation before `pos` if it starts its line
+fn line_indent(input: &str, pos: usize) -> &str {
+    let line_start = input[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let indent = &input[line_start..pos];
+    if indent.trim().is_empty() {
+        indent
+    } else {
+        ""
+    }
+}
+
+/// Remove the indentation of the first line from all lines, e.g. the body of an `@layer` block
+fn dedent(body: &str) -> String {
+    let body = body.trim_start_matches('\n').trim_end();
+    let indent = &body[..body.len() - body.trim_start().len()];
+
+    let mut res = String::with_capacity(body.len() + 1);
+    for line in body.lines() {
+        res.push_str(line.strip_prefix(indent).unwrap_or(line));
+        res.push('\n');
+    }
+    res
+}
+
+/// The escaped class selector of a candidate, e.g. `.hover\:flex`
+fn class_selector(candidate: &str) -> Option<SmolStr> {
+    let mut writer = smol_str::Writer::new();
+    std::fmt::Write::write_char(&mut writer, '.').ok()?;
+    serialize_name(candidate, &mut writer).ok()?;
+    Some(SmolStr::from(writer))
+}
+
+/// Replace `class` with `selector` in `rule` and its nested rules
+fn replace_selector(rule: &mut Rule, class: &str, selector: &str) {
+    if !rule.is_at_rule() {
+        rule.selector = selector
+            .split(',')
+            .map(|s| rule.selector.replace(class, s.trim()))
+            .collect::<Vec<_>>()
+            .join(", ")
+            .into();
+    }
+    for nested in rule.rules.iter_mut() {
+        replace_selector(nested, class, selector);
+    }
+}
+
+fn make_important(rule: &mut Rule) {
+    for decl in rule.decls.iter_mut() {
+        decl.value = format!("{} !important", decl.value).into();
+    }
+    for nested in rule.rules.iter_mut() {
+        make_important(nested);
+    }
+}
+
+/// The position of the `;`, `{` or `}` ending an at-rule prelude
+fn find_prelude_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                i = skip_string(bytes, i, quote);
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            b';' | b'{' | b'}' if depth == 0 => return i,
+            _ => (),
+        }
+        i += 1;
+    }
+    bytes.len()
+}
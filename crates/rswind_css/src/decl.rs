@@ -4,12 +4,13 @@ use std::{
 };
 
 use instance_code::InstanceCode;
+use serde::Serialize;
 use smol_str::SmolStr;
 
 use super::ToCss;
 use crate::writer::Writer;
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, InstanceCode)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, InstanceCode, Serialize)]
 #[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[instance(path = rswind_css)]
 pub struct Decl {
@@ -4,12 +4,13 @@ use std::{
 };
 
 use instance_code::InstanceCode;
+use serde::Serialize;
 use smol_str::SmolStr;
 
 use super::{Decl, ToCss};
 use crate::{writer::Writer, RuleModifier};
 
-#[derive(Debug, Clone, PartialEq, Default, Eq, PartialOrd, Ord, Hash, InstanceCode)]
+#[derive(Debug, Clone, PartialEq, Default, Eq, PartialOrd, Ord, Hash, InstanceCode, Serialize)]
 #[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[instance(path = rswind_css)]
 pub struct Rule {
@@ -58,7 +59,7 @@ impl Rule {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, InstanceCode)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, InstanceCode, Serialize)]
 #[instance(path = rswind_css)]
 pub struct RuleList(pub Vec<Rule>);
 
@@ -1,61 +1,495 @@
+use std::fmt::{self, Display};
+
 use cssparser_macros::match_byte;
 
-use crate::cursor::Cursor;
+use crate::cursor::{Cursor, EOF_CHAR};
+
+/// Where the extractor is, the innermost context is on the top of the stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    /// JavaScript code, `braces` counts the unclosed `{`,
+    /// so that a `}` can close the `${…}` or JSX expression it belongs to
+    Code { braces: usize },
+    /// Inside a template literal, after the backtick or a `${…}` expression
+    Template,
+    /// Inside a JSX tag, e.g. `<div className="flex">` or `</div>`
+    JsxTag { closing: bool },
+    /// Inside the children of a JSX element, text here is not extracted
+    JsxChildren,
+}
+
+/// The last significant token in code, decides whether `/` starts a regex
+/// and whether `<` starts a JSX element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Punct,
+    Keyword,
+    Value,
+}
+
+/// Keywords that can be followed by an expression, e.g. `return /re/`
+const EXPR_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+
+/// Names of class utility functions and class attributes,
+/// see [`EcmaExtractor::with_class_functions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassFunctions {
+    /// Functions and template tags, e.g. `clsx`, `cva`, `cn`, `tw`
+    pub functions: Vec<String>,
+    /// JSX attributes and object properties, e.g. `className`, `class`
+    pub attributes: Vec<String>,
+}
+
+impl Default for ClassFunctions {
+    fn default() -> Self {
+        Self {
+            functions: ["clsx", "cn", "cva", "tw"].map(String::from).to_vec(),
+            attributes: ["className", "class"].map(String::from).to_vec(),
+        }
+    }
+}
+
+/// The language of a script, decides whether a `<` starts a JSX tag
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Syntax {
+    /// JavaScript, JSX is allowed
+    #[default]
+    JavaScript,
+    /// TypeScript without JSX, e.g. `.ts`, a `<` never starts a tag
+    TypeScript,
+    /// TypeScript with JSX, e.g. `.tsx`, generics like `<T>(x: T) => x` are not tags
+    Tsx,
+}
+
+impl Syntax {
+    pub fn from_suffix(suffix: &str) -> Self {
+        match suffix {
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            _ => Self::JavaScript,
+        }
+    }
+}
 
+/// Strings were scanned with this before template literals and JSX were supported
+#[deprecated(note = "strings are scanned by `EcmaExtractor::consume_string`")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StringType {
     SingleQuote,
     DoubleQuote,
-    // TODO: support template string
-    // Template,
 }
 
+/// Where a string is extracted from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin<'a> {
+    /// A string or template literal
+    Literal,
+    /// Arguments of a class function, e.g. `clsx('flex')`
+    Call(&'a str),
+    /// A tagged template, e.g. ``tw`flex` ``
+    Tag(&'a str),
+    /// A class attribute or property, e.g. `className="flex"` or `el.className = 'flex'`
+    Attribute(&'a str),
+}
+
+impl Display for Origin<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal => f.write_str("literal"),
+            Self::Call(name) => write!(f, "{name}()"),
+            Self::Tag(name) => write!(f, "{name}``"),
+            Self::Attribute(name) => f.write_str(name),
+        }
+    }
+}
+
+/// A string extracted with its [`Origin`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extracted<'a> {
+    pub value: &'a str,
+    pub origin: Origin<'a>,
+}
+
+/// Extracts string literals from JavaScript or TypeScript
+///
+/// Static chunks of template literals are extracted and `${…}` expressions are scanned recursively,
+/// regex literals and JSX text are skipped
 pub struct EcmaExtractor<'a> {
     cursor: Cursor<'a>,
     input: &'a str,
-    position: usize,
-    at_start_of: Option<StringType>,
+    stack: Vec<Context>,
+    last: Token,
+    syntax: Syntax,
+    class_functions: Option<&'a ClassFunctions>,
+    /// Open class function calls and attribute expressions, with the bracket depth they started at
+    scopes: Vec<(Origin<'a>, usize)>,
+    /// Depth of brackets, template literals and `${…}` expressions
+    depth: usize,
+    /// The origin of the next token, e.g. a `(` after `clsx`
+    pending: Option<Origin<'a>>,
+    /// The last identifier if it is a class attribute, e.g. `className` in `el.className = ...`
+    attribute: Option<&'a str>,
 }
 
 impl<'a> EcmaExtractor<'a> {
     pub fn new(input: &'a str) -> Self {
-        Self { cursor: Cursor::new(input), input, position: 0, at_start_of: None }
+        Self {
+            cursor: Cursor::new(input),
+            input,
+            stack: vec![Context::Code { braces: 0 }],
+            last: Token::Punct,
+            syntax: Syntax::JavaScript,
+            class_functions: None,
+            scopes: Vec::new(),
+            depth: 0,
+            pending: None,
+            attribute: None,
+        }
     }
 
-    pub fn str_from(&self, start: usize) -> &'a str {
-        self.str_from_to(start, self.position)
+    /// Start in JSX text, e.g. the content of MDX, only tags and `{…}` expressions are scanned,
+    /// strings are extracted in class function mode, see [`EcmaExtractor::with_class_functions`]
+    pub fn jsx_text(input: &'a str, class_functions: &'a ClassFunctions) -> Self {
+        Self {
+            stack: vec![Context::JsxChildren],
+            ..Self::with_class_functions(input, class_functions)
+        }
+    }
+
+    /// Extract only from arguments of `functions`, tagged templates of `functions` and values of `attributes`
+    pub fn with_class_functions(input: &'a str, class_functions: &'a ClassFunctions) -> Self {
+        Self { class_functions: Some(class_functions), ..Self::new(input) }
+    }
+
+    pub fn with_syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Iterate over extracted strings with their [`Origin`]
+    pub fn extracted(mut self) -> impl Iterator<Item = Extracted<'a>> {
+        std::iter::from_fn(move || self.next_extracted())
+    }
+
+    pub fn next_extracted(&mut self) -> Option<Extracted<'a>> {
+        while !self.cursor.is_eof() {
+            let res = match self.context() {
+                Context::Code { .. } => self.scan_code(),
+                Context::Template => self.scan_template(),
+                Context::JsxTag { closing } => self.scan_jsx_tag(closing),
+                Context::JsxChildren => self.scan_jsx_children(),
+            };
+            if res.is_some() {
+                return res;
+            }
+        }
+        None
+    }
+
+    fn str_from_to(&self, from: usize, to: usize) -> Option<&'a str> {
+        (from < to).then(|| &self.input[from..to])
+    }
+
+    fn context(&self) -> Context {
+        *self.stack.last().unwrap_or(&Context::Code { braces: 0 })
+    }
+
+    /// Pop the current context, the top level code is never popped
+    fn pop(&mut self) {
+        if self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    /// Tag a string with the current origin,
+    /// strings outside of class functions and attributes are dropped in class function mode
+    fn emit(&self, value: Option<&'a str>, origin: Option<Origin<'a>>) -> Option<Extracted<'a>> {
+        let origin = origin.or(self.scopes.last().map(|(origin, _)| *origin));
+        match (origin, self.class_functions) {
+            (Some(origin), _) => value.map(|value| Extracted { value, origin }),
+            (None, None) => value.map(|value| Extracted { value, origin: Origin::Literal }),
+            (None, Some(_)) => None,
+        }
     }
 
-    fn str_from_to(&self, from: usize, to: usize) -> &'a str {
-        &self.input[from..to]
+    /// Enter a bracket, template literal or expression, `origin` opens a new scope
+    fn open(&mut self, origin: Option<Origin<'a>>) {
+        if let Some(origin) = origin {
+            self.scopes.push((origin, self.depth));
+        }
+        self.depth += 1;
     }
 
-    pub(crate) fn consume_until_string(&mut self) -> Result<StringType, ()> {
+    fn close(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+        if self.scopes.last().is_some_and(|(_, depth)| *depth == self.depth) {
+            self.scopes.pop();
+        }
+    }
+
+    fn is_class_function(&self, name: &str) -> bool {
+        self.class_functions.is_some_and(|c| c.functions.iter().any(|f| f == name))
+    }
+
+    fn is_class_attribute(&self, name: &str) -> bool {
+        self.class_functions.is_some_and(|c| c.attributes.iter().any(|a| a == name))
+    }
+
+    fn scan_code(&mut self) -> Option<Extracted<'a>> {
         while let Some(c) = self.cursor.try_bump() {
-            // skip non-ascii characters
-            if c as u32 >= 0x80 {
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+
+            let pending = self.pending.take();
+            let attribute = self.attribute.take();
+
+            // identifiers, including non-ascii ones
+            if c as u32 >= 0x80 || c.is_ascii_alphabetic() || c == '_' || c == '$' {
+                let start = self.cursor.pos() - c.len_utf8();
+                self.cursor.eat_while(|c| {
+                    c as u32 >= 0x80 || c.is_ascii_alphanumeric() || c == '_' || c == '$'
+                });
+                let ident = &self.input[start..self.cursor.pos()];
+
+                if self.is_class_function(ident) {
+                    self.pending = match self.cursor.as_str().trim_start().as_bytes().first() {
+                        Some(b'(') => Some(Origin::Call(ident)),
+                        Some(b'`') => Some(Origin::Tag(ident)),
+                        _ => None,
+                    };
+                } else if self.is_class_attribute(ident) {
+                    self.attribute = Some(ident);
+                }
+
+                self.last = match EXPR_KEYWORDS.contains(&ident) {
+                    true => Token::Keyword,
+                    false => Token::Value,
+                };
                 continue;
             }
+
             match_byte! { c as u8,
+                b'\'' | b'"' => {
+                    self.last = Token::Value;
+                    let value = self.consume_string(c);
+                    return self.emit(value, pending);
+                }
+                b'`' => {
+                    self.open(pending);
+                    self.stack.push(Context::Template);
+                    return None;
+                }
                 b'/' => {
                     match self.cursor.first() {
                         '/' => self.consume_comment(),
                         '*' => { self.consume_block_comment(); },
+                        _ if self.last == Token::Value => self.last = Token::Punct,
+                        _ => {
+                            self.consume_regex();
+                            self.last = Token::Value;
+                        }
+                    }
+                }
+                b'<' => {
+                    if self.last != Token::Value && self.at_jsx_tag() {
+                        self.stack.push(Context::JsxTag { closing: false });
+                        return None;
+                    }
+                    self.last = Token::Punct;
+                }
+                b'=' | b':' => {
+                    // `el.className = 'flex'` or `{ className: 'flex' }`
+                    self.pending = attribute.map(Origin::Attribute);
+                    self.last = Token::Punct;
+                }
+                b'(' | b'[' => {
+                    self.open(pending);
+                    self.last = Token::Punct;
+                }
+                b'{' => {
+                    self.open(pending);
+                    if let Some(Context::Code { braces }) = self.stack.last_mut() {
+                        *braces += 1;
+                    }
+                    self.last = Token::Punct;
+                }
+                b'}' => {
+                    self.close();
+                    let nested = self.stack.len() > 1;
+                    match self.stack.last_mut() {
+                        Some(Context::Code { braces: 0 }) if nested => {
+                            // the end of a `${…}` or JSX expression
+                            self.stack.pop();
+                            self.last = Token::Value;
+                            return None;
+                        }
+                        Some(Context::Code { braces }) => *braces = braces.saturating_sub(1),
                         _ => (),
                     }
+                    self.last = Token::Punct;
+                }
+                b'0'..=b'9' => {
+                    self.cursor.eat_while(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_');
+                    self.last = Token::Value;
+                }
+                b')' | b']' => {
+                    self.close();
+                    self.last = Token::Value;
+                }
+                _ => self.last = Token::Punct,
+            }
+        }
+
+        None
+    }
+
+    fn scan_template(&mut self) -> Option<Extracted<'a>> {
+        let start = self.cursor.pos();
+        loop {
+            let end = self.cursor.pos();
+            match self.cursor.bump() {
+                '\\' => {
+                    self.cursor.bump();
+                }
+                '`' => {
+                    let res = self.emit(self.str_from_to(start, end), None);
+                    self.pop();
+                    self.close();
+                    self.last = Token::Value;
+                    return res;
+                }
+                '$' if self.cursor.first() == '{' => {
+                    self.cursor.bump();
+                    self.stack.push(Context::Code { braces: 0 });
+                    self.open(None);
+                    self.last = Token::Punct;
+                    return self.emit(self.str_from_to(start, end), None);
+                }
+                EOF_CHAR if self.cursor.is_eof() => {
+                    return self.emit(self.str_from_to(start, end), None)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn scan_jsx_tag(&mut self, closing: bool) -> Option<Extracted<'a>> {
+        let mut attribute = None;
+        while let Some(c) = self.cursor.try_bump() {
+            match c {
+                'a'..='z' | 'A'..='Z' => {
+                    let start = self.cursor.pos() - 1;
+                    self.cursor.eat_while(|c| {
+                        c.is_ascii_alphanumeric() || matches!(c, '-' | ':' | '.' | '_')
+                    });
+                    let name = &self.input[start..self.cursor.pos()];
+                    attribute = self.is_class_attribute(name).then_some(Origin::Attribute(name));
+                }
+                // attribute strings have no escapes and can span lines
+                '\'' | '"' => {
+                    let start = self.cursor.pos();
+                    self.cursor.eat_until_char(c as u8);
+                    let end = self.cursor.pos();
+                    self.cursor.bump();
+                    let res = self.emit(self.str_from_to(start, end), attribute);
+                    if res.is_some() {
+                        return res;
+                    }
+                }
+                '{' => {
+                    self.stack.push(Context::Code { braces: 0 });
+                    self.open(attribute);
+                    self.last = Token::Punct;
+                    return None;
+                }
+                '/' if self.cursor.first() == '>' => {
+                    // self closing tag
+                    self.cursor.bump();
+                    self.pop();
+                    self.last = Token::Value;
+                    return None;
                 }
-                b'\'' => {
-                    self.at_start_of = Some(StringType::SingleQuote);
-                    return Ok(StringType::SingleQuote);
+                '>' => {
+                    self.pop();
+                    if closing {
+                        // leave the children of the element
+                        if self.context() == Context::JsxChildren {
+                            self.pop();
+                        }
+                    } else {
+                        self.stack.push(Context::JsxChildren);
+                    }
+                    self.last = Token::Value;
+                    return None;
+                }
+                '=' => (),
+                _ => attribute = None,
+            }
+        }
+
+        None
+    }
+
+    fn scan_jsx_children(&mut self) -> Option<Extracted<'a>> {
+        while let Some(c) = self.cursor.try_bump() {
+            match c {
+                '{' => {
+                    self.stack.push(Context::Code { braces: 0 });
+                    self.open(None);
+                    self.last = Token::Punct;
+                    return None;
                 }
-                b'"' => {
-                    self.at_start_of = Some(StringType::DoubleQuote);
-                    return Ok(StringType::DoubleQuote);
+                '<' if matches!(self.cursor.first(), 'a'..='z' | 'A'..='Z' | '/' | '>') => {
+                    let closing = self.cursor.first() == '/';
+                    self.stack.push(Context::JsxTag { closing });
+                    return None;
                 }
                 _ => (),
             }
         }
-        Err(())
+
+        None
+    }
+
+    /// Whether the `<` just consumed starts a JSX tag, e.g. `<div `, `<Foo.Bar>` or `<>`
+    ///
+    /// Rejects `<T,>` style generics, and in TypeScript `<T>(…)` and `<T extends …>`
+    fn at_jsx_tag(&self) -> bool {
+        let rest = self.cursor.as_str().as_bytes();
+        match (rest.first(), self.syntax) {
+            (_, Syntax::TypeScript) => false,
+            (Some(b'>'), _) => true,
+            (Some(c), _) if c.is_ascii_alphabetic() => {
+                let len = rest
+                    .iter()
+                    .position(|c| {
+                        !(c.is_ascii_alphanumeric() || matches!(c, b'.' | b':' | b'-' | b'_'))
+                    })
+                    .unwrap_or(rest.len());
+                let after = &self.cursor.as_str()[len..];
+                if self.syntax == Syntax::Tsx && is_generic_params(after) {
+                    return false;
+                }
+                matches!(rest.get(len), Some(b' ' | b'\t' | b'\n' | b'\r' | b'>' | b'/' | b'{'))
+            }
+            _ => false,
+        }
     }
 
     pub fn consume_comment(&mut self) {
@@ -85,25 +519,45 @@ impl<'a> EcmaExtractor<'a> {
         depth == 0
     }
 
-    pub fn consume_string(&mut self) -> bool {
-        let string_type = self.at_start_of.expect(
-            "consume_string should only be called after consume_until_string has been called",
-        );
-        self.at_start_of = None;
-
-        match string_type {
-            StringType::SingleQuote => self.consume_single_quoted_string(),
-            StringType::DoubleQuote => self.consume_double_quoted_string(),
+    /// Consume a regex literal after the opening `/`, including its flags
+    pub fn consume_regex(&mut self) {
+        let mut in_class = false;
+        while let Some(c) = self.cursor.try_bump() {
+            match c {
+                '\\' => {
+                    self.cursor.bump();
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => break,
+                // not a regex, give up at the end of line
+                '\n' => return,
+                _ => (),
+            }
         }
+        self.cursor.eat_while(|c| c.is_ascii_alphabetic());
+    }
+
+    #[deprecated(note = "use `Cursor::pos` with the input instead")]
+    pub fn str_from(&self, start: usize) -> &'a str {
+        &self.input[start..self.cursor.pos()]
     }
 
+    #[deprecated(note = "use `EcmaExtractor::consume_string` instead")]
     pub fn consume_single_quoted_string(&mut self) -> bool {
+        self.consume_quoted_string('\'')
+    }
+
+    #[deprecated(note = "use `EcmaExtractor::consume_string` instead")]
+    pub fn consume_double_quoted_string(&mut self) -> bool {
+        self.consume_quoted_string('"')
+    }
+
+    fn consume_quoted_string(&mut self, quote: char) -> bool {
         while let Some(c) = self.cursor.try_bump() {
             match c {
-                '\'' => {
-                    return true;
-                }
-                '\\' if self.cursor.first() == '\\' || self.cursor.first() == '\'' => {
+                c if c == quote => return true,
+                '\\' if self.cursor.first() == '\\' || self.cursor.first() == quote => {
                     self.cursor.bump();
                 }
                 _ => (),
@@ -112,19 +566,34 @@ impl<'a> EcmaExtractor<'a> {
         false
     }
 
-    pub fn consume_double_quoted_string(&mut self) -> bool {
+    /// Consume a string after the opening `quote`, returns the content of the string
+    pub fn consume_string(&mut self, quote: char) -> Option<&'a str> {
+        let start = self.cursor.pos();
+        let mut end = start;
         while let Some(c) = self.cursor.try_bump() {
             match c {
-                '"' => {
-                    return true;
-                }
-                '\\' if self.cursor.first() == '\\' || self.cursor.first() == '"' => {
+                '\\' => {
                     self.cursor.bump();
                 }
+                // unterminated string
+                '\n' => break,
+                c if c == quote => break,
                 _ => (),
             }
+            end = self.cursor.pos();
         }
-        false
+        self.str_from_to(start, end)
+    }
+}
+
+/// Whether the rest after `<T` is a TypeScript type parameter list, i.e. `>(` or ` extends `
+fn is_generic_params(rest: &str) -> bool {
+    match rest.strip_prefix('>') {
+        Some(rest) => rest.trim_start().starts_with('('),
+        None => rest
+            .trim_start()
+            .strip_prefix("extends")
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_whitespace())),
     }
 }
 
@@ -132,22 +601,7 @@ impl<'a> Iterator for EcmaExtractor<'a> {
     type Item = &'a str;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cursor.is_eof() {
-            return None;
-        }
-        let res = match self.at_start_of {
-            None => {
-                self.consume_until_string().ok()?;
-                self.next()
-            }
-            Some(_) => {
-                let start = self.cursor.pos();
-                self.consume_string();
-                let end = self.cursor.pos() - 1;
-                Some(self.str_from_to(start, end))
-            }
-        };
-        res
+        self.next_extracted().map(|e| e.value)
     }
 }
 
@@ -212,4 +666,171 @@ mod tests {
         assert_eq!(extractor.next(), Some("this is string"));
         assert_eq!(extractor.next(), None);
     }
+    fn run(input: &str) -> Vec<&str> {
+        EcmaExtractor::new(input).collect()
+    }
+
+    #[test]
+    fn test_template_literal() {
+        let input = r#"const cls = `flex ${active ? 'bg-red-500' : ''} p-4`"#;
+        assert_eq!(run(input), ["flex ", "bg-red-500", " p-4"]);
+    }
+
+    #[test]
+    fn test_nested_template_literal() {
+        let input = r#"`a ${`b ${c ? "d" : `e`} f`} g` 'h'"#;
+        assert_eq!(run(input), ["a ", "b ", "d", "e", " f", " g", "h"]);
+    }
+
+    #[test]
+    fn test_template_literal_with_braces() {
+        let input = r#"`a ${fn({ b: 'c' })} d ${`\${e}`}` 'f'"#;
+        assert_eq!(run(input), ["a ", "c", " d ", "\\${e}", "f"]);
+    }
+
+    #[test]
+    fn test_regex_literal() {
+        let input = r#"
+        const re = /'/g
+        const re2 = str.replace(/["`]/, '')
+        if (x) return /[/]'/.test(s)
+        'flex'
+        "#;
+        assert_eq!(run(input), ["flex"]);
+    }
+
+    #[test]
+    fn test_division() {
+        let input = r#"const half = width / 2 + 'px'; const b = (a) / 2 / 'c'"#;
+        assert_eq!(run(input), ["px", "c"]);
+    }
+
+    #[test]
+    fn test_jsx_text() {
+        let input = r#"
+        const App = () => (
+            <p className="text-sm">
+                Don't break here, it's fine
+                {cond && <span className={`font-bold ${size}`}>it's {"flex"}</span>}
+                <br />
+            </p>
+        )
+        const after = 'after'
+        "#;
+        assert_eq!(run(input), ["text-sm", "font-bold ", "flex", "after"]);
+
+        let class_functions = ClassFunctions::default();
+        let input = r#"<p title="not this" className="text-sm">it's {"no"} {cn('flex')}</p>"#;
+        let res = EcmaExtractor::jsx_text(input, &class_functions).collect::<Vec<_>>();
+        assert_eq!(res, ["text-sm", "flex"]);
+    }
+
+    #[test]
+    fn test_generic_is_not_jsx() {
+        let input = r#"const f = <T,>(x: T) => x; const y = a < b ? 'c' : 'd'"#;
+        assert_eq!(run(input), ["c", "d"]);
+    }
+
+    fn run_tsx(input: &str) -> Vec<&str> {
+        EcmaExtractor::new(input).with_syntax(Syntax::Tsx).collect()
+    }
+
+    #[test]
+    fn test_tsx_generic_arrow() {
+        let input = r#"
+        const id = <T>(x: T) => x
+        const a = 'flex'
+        const App = () => <T>(text)</T>
+        "#;
+        assert_eq!(run_tsx(input), ["flex"]);
+        assert!(run(input).is_empty());
+    }
+
+    #[test]
+    fn test_tsx_generic_extends() {
+        let input = r#"
+        const first = <T extends unknown[]>(list: T) => list[0]
+        const b = "grid"
+        const c = <T extends="x">{'not-a-generic'}</T>
+        "#;
+        assert_eq!(run_tsx(input), ["grid", "x", "not-a-generic"]);
+    }
+
+    #[test]
+    fn test_ts_type_assertion() {
+        let input = r#"const el = <HTMLElement>document.body; el.className = 'hidden'"#;
+        let res = EcmaExtractor::new(input).with_syntax(Syntax::TypeScript).collect::<Vec<_>>();
+        assert_eq!(res, ["hidden"]);
+        assert!(run(input).is_empty());
+    }
+    fn run_class<'a>(
+        input: &'a str,
+        class_functions: &'a ClassFunctions,
+    ) -> Vec<(&'a str, String)> {
+        EcmaExtractor::with_class_functions(input, class_functions)
+            .extracted()
+            .map(|e| (e.value, e.origin.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_class_functions() {
+        let input = r#"
+        const title = "hello world"
+        const a = clsx('flex', { 'bg-red-500': isError }, cond && "p-4", fn('not this'))
+        const b = cn(`text-sm ${big ? 'text-lg' : ''}`)
+        const button = cva('rounded', {
+            variants: { intent: { primary: 'bg-blue-500', secondary: ['bg-gray-100', 'text-black'] } },
+        })
+        const c = tw`mt-2 ${'mb-2'}`
+        log('not this either')
+        "#;
+
+        assert_eq!(
+            run_class(input, &ClassFunctions::default()),
+            [
+                ("flex", "clsx()".into()),
+                ("bg-red-500", "clsx()".into()),
+                ("p-4", "clsx()".into()),
+                ("not this", "clsx()".into()),
+                ("text-sm ", "cn()".into()),
+                ("text-lg", "cn()".into()),
+                ("rounded", "cva()".into()),
+                ("bg-blue-500", "cva()".into()),
+                ("bg-gray-100", "cva()".into()),
+                ("text-black", "cva()".into()),
+                ("mt-2 ", "tw``".into()),
+                ("mb-2", "tw``".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_class_attributes() {
+        let input = r#"
+        el.className = 'block'
+        el.title = 'not this'
+        if (el.className === 'not-this') {}
+        const props = { className: "hidden", id: "not this" }
+        const App = () => (
+            <div id="app" className="flex" class='grid' data-x={'not this'}>
+                <span className={clsx('font-bold', active && 'underline')} title={"no"}>
+                    {'not this'}
+                </span>
+            </div>
+        )
+        "#;
+
+        assert_eq!(
+            run_class(input, &ClassFunctions::default()),
+            [
+                ("block", "className".into()),
+                ("hidden", "className".into()),
+                ("flex", "className".into()),
+                ("grid", "class".into()),
+                ("font-bold", "clsx()".into()),
+                ("underline", "clsx()".into()),
+            ]
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    borrow::Cow,
+    ops::{Deref, DerefMut},
+};
 
 use cssparser::match_byte;
 
@@ -9,7 +12,7 @@ pub enum StringType {
     DoubleQuote,
 }
 
-/// Extractor for HTML / Vue
+/// Extractor for HTML / Vue / Svelte / Astro
 ///
 /// We only care about the attribute value and the JS expressions
 pub struct HtmlExtractor<'a> {
@@ -17,6 +20,10 @@ pub struct HtmlExtractor<'a> {
     cursor: Cursor<'a>,
     in_js: Option<Box<dyn Iterator<Item = &'a str> + Send + Sync + 'a>>,
     in_start_tag: bool,
+    /// Name of the last start tag
+    tag: &'a str,
+    /// Depth of `<svg>` elements, only `class` attributes are extracted inside them
+    svg_depth: usize,
     options: HtmlExtractOptions,
 }
 
@@ -26,6 +33,7 @@ pub enum FileType {
     Html,
     Vue,
     Svelte,
+    Astro,
 }
 
 impl FileType {
@@ -33,9 +41,20 @@ impl FileType {
         match suffix {
             "vue" => Self::Vue,
             "svelte" => Self::Svelte,
+            "astro" => Self::Astro,
             _ => Self::Html,
         }
     }
+
+    /// Whether the attribute binds a class object or array, e.g. `:class="{ active: isActive }"`
+    fn is_class_binding(&self, name: &str) -> bool {
+        match self {
+            Self::Vue => matches!(name, ":class" | "v-bind:class"),
+            Self::Astro => name == "class:list",
+            Self::Svelte => name == "class",
+            Self::Html => false,
+        }
+    }
 }
 
 #[derive(Default)]
@@ -44,9 +63,9 @@ pub struct HtmlExtractOptions {
     pub file_type: FileType,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CandidateValue<'a> {
-    Plain(&'a str),
+    Plain(Cow<'a, str>),
     Ecma,
 }
 
@@ -71,6 +90,8 @@ impl<'a> HtmlExtractor<'a> {
             cursor: Cursor::new(input),
             in_js: None,
             in_start_tag: false,
+            tag: "",
+            svg_depth: 0,
             options: HtmlExtractOptions::default(),
         }
     }
@@ -117,14 +138,26 @@ impl<'a> HtmlExtractor<'a> {
         // valid start tag
         loop {
             self.eat_until_after_char(b'<');
-            if self.first() != '/' && !self.first().is_ascii_whitespace() {
+            if self.eat_str("!--") {
+                self.consume_comment();
+                continue;
+            }
+            if self.first() == '/' {
+                if self.svg_depth > 0 && self.eat_str("/svg") {
+                    self.svg_depth -= 1;
+                }
+                continue;
+            }
+            if !self.first().is_ascii_whitespace() {
                 break;
             }
         }
         self.in_start_tag = true;
 
         // eat tag name
-        match self.consume_tag_name() {
+        self.tag = self.consume_tag_name();
+        match self.tag {
+            "svg" => self.svg_depth += 1,
             // use `EcmaExtractor` to extract JS str lit
             "script" => {
                 // skip start tag
@@ -179,55 +212,124 @@ impl<'a> HtmlExtractor<'a> {
             })
         });
 
-        match self.bump() {
-            '=' => {
-                // jump the `"` or `{` (svelte)
-                let start = self.bump();
+        // svelte `class:active` shorthand, the candidate is the attribute name
+        let directive = match self.options.file_type {
+            FileType::Svelte => name.strip_prefix("class:"),
+            _ => None,
+        };
 
-                // filter out invalid start
-                if start != '"' && start != '{' {
+        // whitespace is allowed around `=`, e.g. `<div class = "flex">`
+        self.consume_whitespace();
+        match self.first() {
+            '=' => {
+                self.bump();
+                self.consume_whitespace();
+                // jump the `"`, `'` or `{` (svelte, astro)
+                let (value, braced) = match self.first() {
+                    quote @ ('"' | '\'') => {
+                        self.bump();
+                        let value = self.consume(|c| c.eat_until_char(quote as u8));
+                        self.bump();
+                        (value, false)
+                    }
+                    '{' => {
+                        self.bump();
+                        (self.consume_braced(), true)
+                    }
+                    // filter out invalid start, e.g. `class=>`
+                    '>' => return None,
+                    _ if self.is_eof() => return None,
+                    // unquoted, e.g. `class=flex`
+                    _ => (
+                        self.consume(|c| c.eat_until(|c| c.is_ascii_whitespace() || c == '>')),
+                        false,
+                    ),
+                };
+
+                if self.svg_depth > 0 && name != "class" {
                     return None;
                 }
 
-                let end = if start == '{' { b'}' } else { b'"' };
-
-                let value = self.consume(|c| c.eat_until_char(end));
-                self.bump();
-
                 // TODO: determine these functions at init, prevent runtime check
-                if self.options.class_only && !name.starts_with("class") && !name.starts_with(':') {
+                if self.options.class_only
+                    && !name.starts_with("class")
+                    && !name.starts_with(':')
+                    && name != "v-bind:class"
+                {
                     return None;
                 }
 
-                // svelte class
-                if self.options.file_type == FileType::Svelte && name.starts_with("class:") {
-                    self.extend_js_extractor(EcmaExtractor::new(value));
-                    return Some(CandidateValue::Plain(name.trim_start_matches("class:")));
+                let file_type = self.options.file_type;
+                let is_expression = match file_type {
+                    FileType::Vue => name.starts_with(':') || name.starts_with("v-"),
+                    FileType::Svelte | FileType::Astro => directive.is_some() || braced,
+                    FileType::Html => false,
+                };
+
+                if !is_expression {
+                    return Some(CandidateValue::Plain(decode_entities(value)));
                 }
 
-                // vue
-                if self.options.file_type == FileType::Vue
-                    && (name.starts_with(':') || name.starts_with("v-"))
-                {
-                    self.extend_js_extractor(EcmaExtractor::new(value));
-                    return Some(CandidateValue::Ecma);
+                self.extend_js_extractor(EcmaExtractor::new(value));
+                if file_type.is_class_binding(name) {
+                    self.extend_js_extractor(object_keys(value).into_iter());
                 }
 
-                return Some(CandidateValue::Plain(value));
+                return Some(match directive {
+                    Some(class) => CandidateValue::Plain(Cow::Borrowed(class)),
+                    None => CandidateValue::Ecma,
+                });
             }
             '>' => {
+                self.bump();
                 self.in_start_tag = false;
             }
-            '/' if self.first() == '>' => {
-                // self closed tag
+            '/' => {
                 self.bump();
-                self.in_start_tag = false;
+                // self closed tag
+                if self.first() == '>' {
+                    self.bump();
+                    self.in_start_tag = false;
+                    // `<svg ... />` has no subtree
+                    if self.tag == "svg" {
+                        self.svg_depth = self.svg_depth.saturating_sub(1);
+                    }
+                }
             }
-            _ => {
-                self.consume_whitespace();
+            // the next attribute
+            _ => (),
+        }
+        directive.map(|class| CandidateValue::Plain(Cow::Borrowed(class)))
+    }
+
+    /// Consume a comment after the `<!--`
+    fn consume_comment(&mut self) {
+        while !self.is_eof() {
+            self.eat_until_after_char(b'-');
+            if self.eat_str("->") {
+                break;
             }
         }
-        None
+    }
+
+    /// Consume a `{…}` attribute value after the `{`, nested braces and strings are skipped
+    fn consume_braced(&mut self) -> &'a str {
+        let start = self.pos();
+        let mut depth = 0usize;
+        let mut end = self.pos();
+        while let Some(c) = self.try_bump() {
+            match c {
+                '\'' | '"' | '`' => {
+                    self.eat_until_after_char(c as u8);
+                }
+                '{' => depth += 1,
+                '}' if depth == 0 => break,
+                '}' => depth -= 1,
+                _ => (),
+            }
+            end = self.pos();
+        }
+        &self.input[start..end]
     }
 
     fn next_js(&mut self) -> Option<&'a str> {
@@ -243,12 +345,12 @@ impl<'a> HtmlExtractor<'a> {
 }
 
 impl<'a> Iterator for HtmlExtractor<'a> {
-    type Item = &'a str;
+    type Item = Cow<'a, str>;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             if let Some(js_lit) = self.next_js() {
-                return Some(js_lit);
+                return Some(Cow::Borrowed(js_lit));
             }
 
             if self.is_eof() {
@@ -266,6 +368,101 @@ impl<'a> Iterator for HtmlExtractor<'a> {
     }
 }
 
+/// Decode character references like `&quot;` and `&#39;` in an attribute value
+///
+/// The value is borrowed as is without any `&`
+fn decode_entities(value: &str) -> Cow<'_, str> {
+    if !value.contains('&') {
+        return Cow::Borrowed(value);
+    }
+
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..].find(';').filter(|len| *len <= 8).map(|len| &rest[1..=len]);
+        match entity.and_then(decode_entity) {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[entity.map_or(0, str::len) + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    Cow::Owned(decoded)
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        // not ASCII whitespace, so the value is not split at it
+        "nbsp" => Some('\u{a0}'),
+        _ => {
+            let code = entity.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Unquoted keys of object literals in a class binding,
+/// e.g. `active` in `{ active: isActive }` or the shorthand `{ active }`
+fn object_keys(expr: &str) -> Vec<&str> {
+    let bytes = expr.as_bytes();
+    let mut keys = Vec::new();
+    let mut brackets = Vec::new();
+    let mut prev = b'\0';
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        match c {
+            b'\'' | b'"' | b'`' => {
+                i += memchr::memchr(c, &bytes[i + 1..]).map_or(bytes.len(), |n| n + 1);
+            }
+            b'{' | b'[' | b'(' => brackets.push(c),
+            b'}' | b']' | b')' => {
+                brackets.pop();
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' || c == b'$' => {
+                let len = bytes[i..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$'))
+                    .count();
+                let next = bytes[i + len..].iter().find(|c| !c.is_ascii_whitespace());
+                if brackets.last() == Some(&b'{')
+                    && matches!(prev, b'{' | b',')
+                    && matches!(next, Some(b':' | b',' | b'}') | None)
+                {
+                    keys.push(&expr[i..i + len]);
+                }
+                i += len;
+                prev = c;
+                continue;
+            }
+            _ => (),
+        }
+        if !c.is_ascii_whitespace() {
+            prev = c;
+        }
+        i += 1;
+    }
+    keys
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,18 +1,26 @@
+use std::{borrow::Cow, fmt, sync::Arc};
+
 use cssparser::match_byte;
-use ecma::EcmaExtractor;
-use html::HtmlExtractor;
+use ecma::{ClassFunctions, EcmaExtractor, Origin, Syntax};
+use html::{FileType, HtmlExtractor};
+use markdown::MarkdownExtractor;
 use rayon::iter::ParallelIterator;
 use rswind_common::iter::MaybeParallelIterator;
+use rust::RustExtractor;
 use rustc_hash::FxHashSet as HashSet;
+use template::{Dialect, TemplateExtractor};
 
 pub mod css;
 pub mod cursor;
 pub mod ecma;
 pub mod html;
 pub mod item;
+pub mod markdown;
+pub mod rust;
+pub mod template;
 
 pub trait Extractable<'a> {
-    fn extract(self) -> HashSet<&'a str>;
+    fn extract(self) -> HashSet<Cow<'a, str>>;
 }
 
 pub struct BasicExtractor<'i> {
@@ -38,56 +46,163 @@ impl<'i> BasicExtractor<'i> {
 }
 
 impl<'a> Extractable<'a> for &'a str {
-    fn extract(self) -> HashSet<&'a str> {
-        BasicExtractor::new(self).extract_inner()
+    fn extract(self) -> HashSet<Cow<'a, str>> {
+        BasicExtractor::new(self).extract_inner().into_iter().map(Cow::Borrowed).collect()
+    }
+}
+
+/// A user defined extraction function, e.g. `|input| MyExtractor::new(input).extract()`
+///
+/// Candidates either borrow from the input or are owned, e.g. when returned from JavaScript
+pub type ExtractFn = dyn for<'a> Fn(&'a str) -> HashSet<Cow<'a, str>> + Send + Sync;
+
+/// A user defined extractor, used for in-house file formats
+#[derive(Clone)]
+pub struct CustomExtractor(Arc<ExtractFn>);
+
+impl CustomExtractor {
+    pub fn new(
+        f: impl for<'a> Fn(&'a str) -> HashSet<Cow<'a, str>> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for CustomExtractor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CustomExtractor")
     }
 }
 
 #[derive(Debug)]
 pub enum InputKind {
-    Html,
+    Html(FileType),
     // Css,
     Ecma,
+    /// TypeScript, without or with JSX
+    TypeScript(Syntax),
+    Markdown,
+    /// Markdown with JSX and `{…}` expressions
+    Mdx,
+    Rust,
+    Template(Dialect),
+    Custom(CustomExtractor),
     Unknown,
 }
 
 impl From<&str> for InputKind {
     fn from(kind: &str) -> Self {
         match kind {
-            "html" | "vue" | "svelte" => InputKind::Html,
+            "html" | "vue" | "svelte" | "astro" => InputKind::Html(FileType::from_suffix(kind)),
             // "css" => InputKind::Css,
-            "js" | "ts" | "jsx" | "tsx" | "mjs" | "mts" | "cjs" | "cts" => InputKind::Ecma,
+            "js" | "jsx" | "mjs" | "cjs" => InputKind::Ecma,
+            "ts" | "tsx" | "mts" | "cts" => InputKind::TypeScript(Syntax::from_suffix(kind)),
+            "md" | "markdown" => InputKind::Markdown,
+            "mdx" => InputKind::Mdx,
+            "rs" => InputKind::Rust,
+            "blade.php" => InputKind::Template(Dialect::Blade),
+            "twig" => InputKind::Template(Dialect::Twig),
+            "jinja" | "jinja2" | "j2" | "njk" => InputKind::Template(Dialect::Jinja),
+            "erb" => InputKind::Template(Dialect::Erb),
+            "heex" | "leex" => InputKind::Template(Dialect::Heex),
+            "ex" => InputKind::Template(Dialect::Elixir),
+            "cshtml" | "razor" => InputKind::Template(Dialect::Razor),
             _ => InputKind::Unknown,
         }
     }
 }
 
 pub trait UniqueCandidate<'a> {
-    fn filter_invalid(self) -> HashSet<&'a str>;
+    fn filter_invalid(self) -> HashSet<Cow<'a, str>>;
 }
 
-impl<'a, T: Iterator<Item = &'a str>> UniqueCandidate<'a> for T {
-    fn filter_invalid(self) -> HashSet<&'a str> {
-        self.flat_map(|s| s.split_ascii_whitespace())
-            .filter(|s| {
-                match_byte! { *s.as_bytes().first().unwrap_or(&b'\0'),
-                    b'a'..=b'z' | b'-' | b'!' | b'[' => true,
-                    _ => false,
+impl<'a, T, C> UniqueCandidate<'a> for T
+where
+    T: Iterator<Item = C>,
+    C: Into<Cow<'a, str>>,
+{
+    fn filter_invalid(self) -> HashSet<Cow<'a, str>> {
+        let mut res = HashSet::default();
+        for value in self {
+            match value.into() {
+                Cow::Borrowed(s) => {
+                    res.extend(s.split_ascii_whitespace().filter(is_candidate).map(Cow::Borrowed));
                 }
-            })
-            .collect::<HashSet<_>>()
+                // not a slice of the input, e.g. a decoded HTML attribute
+                Cow::Owned(s) => res.extend(
+                    s.split_ascii_whitespace().filter(is_candidate).map(|s| Cow::Owned(s.into())),
+                ),
+            }
+        }
+        res
     }
 }
 
+fn is_candidate(s: &&str) -> bool {
+    match_byte! { *s.as_bytes().first().unwrap_or(&b'\0'),
+        b'a'..=b'z' | b'-' | b'!' | b'[' => true,
+        _ => false,
+    }
+}
+
+/// A transform applied to each candidate of a file before they are deduped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transform {
+    /// Strip a prefix if present, e.g. `x-` turns `x-flex` into `flex`
+    StripPrefix(String),
+    /// Strip a suffix if present
+    StripSuffix(String),
+}
+
+impl Transform {
+    pub fn apply<'a>(&self, candidate: Cow<'a, str>) -> Cow<'a, str> {
+        match candidate {
+            Cow::Borrowed(c) => Cow::Borrowed(self.strip(c).unwrap_or(c)),
+            Cow::Owned(c) => match self.strip(&c) {
+                Some(stripped) => Cow::Owned(stripped.to_owned()),
+                None => Cow::Owned(c),
+            },
+        }
+    }
+
+    fn strip<'a>(&self, candidate: &'a str) -> Option<&'a str> {
+        match self {
+            Transform::StripPrefix(prefix) => candidate.strip_prefix(prefix.as_str()),
+            Transform::StripSuffix(suffix) => candidate.strip_suffix(suffix.as_str()),
+        }
+    }
+}
+
+/// Options of [`Extractor`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractOptions {
+    /// Extract only from class functions and class attributes in JavaScript,
+    /// e.g. `clsx('flex')`, ``tw`flex` `` and `className="flex"`
+    pub class_functions: Option<ClassFunctions>,
+    /// Extract from fenced code blocks in markdown, skipped by default
+    pub code_blocks: bool,
+    /// Transforms applied to candidates in order
+    pub transforms: Vec<Transform>,
+}
+
+static DEFAULT_OPTIONS: ExtractOptions =
+    ExtractOptions { class_functions: None, code_blocks: false, transforms: Vec::new() };
+
 #[derive(Debug)]
 pub struct Extractor<'a> {
     haystack: &'a str,
     kind: InputKind,
+    options: &'a ExtractOptions,
 }
 
 impl<'a> Extractor<'a> {
     pub fn new(haystack: &'a str, kind: impl Into<InputKind>) -> Self {
-        Self { haystack, kind: kind.into() }
+        Self { haystack, kind: kind.into(), options: &DEFAULT_OPTIONS }
+    }
+
+    pub fn with_options(mut self, options: &'a ExtractOptions) -> Self {
+        self.options = options;
+        self
     }
 }
 
@@ -103,34 +218,87 @@ impl<'a, K: Into<InputKind>> From<(&'a str, K)> for Extractor<'a> {
     }
 }
 
+impl<'a> Extractor<'a> {
+    fn ecma_extractor(&self, syntax: Syntax) -> EcmaExtractor<'a> {
+        let extractor = match &self.options.class_functions {
+            Some(class_functions) => {
+                EcmaExtractor::with_class_functions(self.haystack, class_functions)
+            }
+            None => EcmaExtractor::new(self.haystack),
+        };
+        extractor.with_syntax(syntax)
+    }
+
+    /// Candidates of JavaScript and TypeScript inputs with their [`Origin`], for diagnostics
+    ///
+    /// Other inputs don't record origins, nothing is returned for them
+    pub fn origins(&self) -> Vec<(Cow<'a, str>, Origin<'a>)> {
+        let syntax = match self.kind {
+            InputKind::Ecma => Syntax::JavaScript,
+            InputKind::TypeScript(syntax) => syntax,
+            _ => return Vec::new(),
+        };
+        let transforms = &self.options.transforms;
+        self.ecma_extractor(syntax)
+            .extracted()
+            .flat_map(|e| {
+                e.value.split_ascii_whitespace().filter(is_candidate).map(move |c| {
+                    (transforms.iter().fold(Cow::Borrowed(c), |c, t| t.apply(c)), e.origin)
+                })
+            })
+            .collect()
+    }
+}
+
 impl<'a> Extractable<'a> for Extractor<'a> {
-    fn extract(self) -> HashSet<&'a str> {
-        match self.kind {
-            InputKind::Html => HtmlExtractor::new(self.haystack).filter_invalid(),
-            InputKind::Ecma => EcmaExtractor::new(self.haystack).filter_invalid(),
-            InputKind::Unknown => BasicExtractor::new(self.haystack).extract_inner(),
+    fn extract(self) -> HashSet<Cow<'a, str>> {
+        let transforms = &self.options.transforms;
+        let candidates = match self.kind {
+            InputKind::Html(file_type) => HtmlExtractor::new(self.haystack)
+                .apply_options(|o| o.file_type = file_type)
+                .filter_invalid(),
+            InputKind::Ecma => self.ecma_extractor(Syntax::JavaScript).filter_invalid(),
+            InputKind::TypeScript(syntax) => self.ecma_extractor(syntax).filter_invalid(),
+            InputKind::Markdown => MarkdownExtractor::new(self.haystack)
+                .with_code_blocks(self.options.code_blocks)
+                .filter_invalid(),
+            InputKind::Mdx => MarkdownExtractor::mdx(self.haystack)
+                .with_code_blocks(self.options.code_blocks)
+                .with_class_functions(self.options.class_functions.as_ref())
+                .filter_invalid(),
+            InputKind::Rust => RustExtractor::new(self.haystack).filter_invalid(),
+            InputKind::Template(dialect) => {
+                TemplateExtractor::new(self.haystack, dialect).filter_invalid()
+            }
+            InputKind::Custom(CustomExtractor(f)) => f(self.haystack),
+            InputKind::Unknown => self.haystack.extract(),
+        };
+
+        if transforms.is_empty() {
+            return candidates;
         }
+        candidates.into_iter().map(|c| transforms.iter().fold(c, |c, t| t.apply(c))).collect()
     }
 }
 
 pub trait CollectExtracted<'a> {
-    fn collect_extracted(self) -> HashSet<&'a str>;
+    fn collect_extracted(self) -> HashSet<Cow<'a, str>>;
 }
 
 impl<'a, I: Iterator<Item = T>, T: Into<Extractor<'a>>> CollectExtracted<'a> for I {
-    fn collect_extracted(self) -> HashSet<&'a str> {
+    fn collect_extracted(self) -> HashSet<Cow<'a, str>> {
         self.map(Into::into).flat_map(Extractable::extract).collect::<HashSet<_>>()
     }
 }
 
 pub trait ParCollectExtracted<'a> {
-    fn collect_extracted(self) -> HashSet<&'a str>;
+    fn collect_extracted(self) -> HashSet<Cow<'a, str>>;
 }
 
 impl<'a, I: ParallelIterator<Item = T>, T: Into<Extractor<'a>> + Send> ParCollectExtracted<'a>
     for I
 {
-    fn collect_extracted(self) -> HashSet<&'a str> {
+    fn collect_extracted(self) -> HashSet<Cow<'a, str>> {
         self.map(Into::into).map(Extractable::extract).reduce(HashSet::default, |mut acc, i| {
             acc.extend(i);
             acc
@@ -139,7 +307,7 @@ impl<'a, I: ParallelIterator<Item = T>, T: Into<Extractor<'a>> + Send> ParCollec
 }
 
 pub trait MaybeParCollectExtracted<'a> {
-    fn collect_extracted(self) -> HashSet<&'a str>;
+    fn collect_extracted(self) -> HashSet<Cow<'a, str>>;
 }
 
 impl<'a, I, T> MaybeParCollectExtracted<'a> for I
@@ -147,7 +315,7 @@ where
     I: MaybeParallelIterator<Item = T>,
     T: Into<Extractor<'a>> + Send,
 {
-    fn collect_extracted(self) -> HashSet<&'a str> {
+    fn collect_extracted(self) -> HashSet<Cow<'a, str>> {
         self.map(Into::into).map(Extractable::extract).reduce(HashSet::default, |mut acc, i| {
             acc.extend(i);
             acc
@@ -0,0 +1,292 @@
+use std::{borrow::Cow, sync::OnceLock};
+
+use crate::{
+    ecma::{ClassFunctions, EcmaExtractor},
+    html::HtmlExtractor,
+    Extractable, Extractor,
+};
+
+type Candidates<'a> = Box<dyn Iterator<Item = Cow<'a, str>> + 'a>;
+
+/// Extracts candidates from markdown and MDX
+///
+/// Plain prose is ignored, only class attributes of embedded HTML and class values of front-matter
+/// are scanned. MDX also scans class attributes of JSX tags and class function calls in `{…}`
+/// expressions, like `className="p-4"` and `clsx('flex')`.
+/// Fenced code blocks are skipped unless [`MarkdownExtractor::with_code_blocks`] is set,
+/// then they are extracted by their language, e.g. ```` ```html ````
+pub struct MarkdownExtractor<'a> {
+    input: &'a str,
+    /// Byte offset of the next line
+    pos: usize,
+    code_blocks: bool,
+    mdx: bool,
+    class_functions: Option<&'a ClassFunctions>,
+    front_matter: bool,
+    inner: Option<Candidates<'a>>,
+}
+
+/// An opening code fence, e.g. ```` ```tsx ```` or `~~~`
+struct Fence<'a> {
+    marker: char,
+    len: usize,
+    lang: &'a str,
+}
+
+impl<'a> Fence<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let line = line.trim_start();
+        let marker = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+        let len = line.len() - line.trim_start_matches(marker).len();
+        (len >= 3).then(|| Self {
+            marker,
+            len,
+            lang: line[len..].split_whitespace().next().unwrap_or_default(),
+        })
+    }
+
+    fn is_closed_by(&self, line: &str) -> bool {
+        let line = line.trim();
+        let len = line.len() - line.trim_start_matches(self.marker).len();
+        len >= self.len && len == line.len()
+    }
+}
+
+impl<'a> MarkdownExtractor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            code_blocks: false,
+            mdx: false,
+            class_functions: None,
+            front_matter: true,
+            inner: None,
+        }
+    }
+
+    /// Extract from MDX, where prose can contain JSX tags and `{…}` expressions
+    pub fn mdx(input: &'a str) -> Self {
+        Self { mdx: true, ..Self::new(input) }
+    }
+
+    /// Extract candidates from fenced code blocks
+    pub fn with_code_blocks(mut self, code_blocks: bool) -> Self {
+        self.code_blocks = code_blocks;
+        self
+    }
+
+    /// Class functions and attributes scanned in MDX, default to [`ClassFunctions::default`]
+    pub fn with_class_functions(mut self, class_functions: Option<&'a ClassFunctions>) -> Self {
+        self.class_functions = class_functions;
+        self
+    }
+
+    fn next_line(&mut self) -> Option<&'a str> {
+        if self.pos >= self.input.len() {
+            return None;
+        }
+        let rest = &self.input[self.pos..];
+        let line = rest.split_inclusive('\n').next().unwrap_or(rest);
+        self.pos += line.len();
+        Some(line.trim_end_matches(['\n', '\r']))
+    }
+
+    /// Consume lines until the next code fence or the end of input
+    fn next_block(&mut self) -> Option<Candidates<'a>> {
+        if std::mem::take(&mut self.front_matter) {
+            if let Some(values) = self.consume_front_matter() {
+                return Some(values);
+            }
+        }
+
+        let start = self.pos;
+        let mut end = self.pos;
+        while let Some(line) = self.next_line() {
+            let Some(fence) = Fence::parse(line) else {
+                end = self.pos;
+                continue;
+            };
+
+            let code_start = self.pos;
+            let mut code_end = self.pos;
+            while let Some(line) = self.next_line() {
+                if fence.is_closed_by(line) {
+                    break;
+                }
+                code_end = self.pos;
+            }
+
+            let prose = self.prose(&self.input[start..end]);
+            if !self.code_blocks {
+                return Some(Box::new(prose));
+            }
+            let code = Extractor::new(&self.input[code_start..code_end], fence.lang).extract();
+            return Some(Box::new(prose.chain(code)));
+        }
+
+        (start < self.input.len()).then(|| self.prose(&self.input[start..end]))
+    }
+
+    /// Text between code blocks, only HTML class attributes are scanned unless it is MDX
+    fn prose(&self, text: &'a str) -> Candidates<'a> {
+        match self.mdx {
+            true => {
+                static DEFAULT: OnceLock<ClassFunctions> = OnceLock::new();
+                let class_functions =
+                    self.class_functions.unwrap_or_else(|| DEFAULT.get_or_init(Default::default));
+                Box::new(EcmaExtractor::jsx_text(text, class_functions).map(Cow::Borrowed))
+            }
+            false => Box::new(HtmlExtractor::new(text).apply_options(|o| o.class_only = true)),
+        }
+    }
+
+    /// YAML front-matter between `---` lines,
+    /// string values and list items of class keys like `class` or `bodyClasses` are extracted
+    fn consume_front_matter(&mut self) -> Option<Candidates<'a>> {
+        if self.next_line().filter(|line| line.trim_end() == "---").is_none() {
+            self.pos = 0;
+            return None;
+        }
+
+        let mut values = Vec::new();
+        // the key of the current value or list
+        let mut key = "";
+        while let Some(line) = self.next_line() {
+            if line.trim_end() == "---" {
+                return Some(Box::new(values.into_iter().map(Cow::Borrowed)));
+            }
+
+            let line = line.trim();
+            let value = match line.strip_prefix("- ") {
+                Some(item) => item,
+                None => match line.split_once(':') {
+                    Some((k, value)) => {
+                        key = k.trim();
+                        value
+                    }
+                    None => "",
+                },
+            };
+            if !key.to_ascii_lowercase().contains("class") {
+                continue;
+            }
+            let value = value.trim();
+
+            match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                Some(list) => values.extend(list.split(',').map(unquote)),
+                None => values.push(unquote(value)),
+            }
+            values.retain(|v| !v.is_empty());
+        }
+
+        // not closed, treat it as content
+        self.pos = 0;
+        None
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    ["\"", "'"]
+        .iter()
+        .find_map(|q| value.strip_prefix(q).and_then(|v| v.strip_suffix(q)))
+        .unwrap_or(value)
+}
+
+impl<'a> Iterator for MarkdownExtractor<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.inner.as_mut().and_then(Iterator::next) {
+                return Some(item);
+            }
+            self.inner = Some(self.next_block()?);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(input: &str, code_blocks: bool) -> Vec<Cow<'_, str>> {
+        let mut res =
+            MarkdownExtractor::new(input).with_code_blocks(code_blocks).collect::<Vec<_>>();
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn test_prose_is_ignored() {
+        let input = "# Title\n\nSome prose, it's 'not' a \"candidate\" list.\n";
+        assert!(run(input, false).is_empty());
+    }
+
+    #[test]
+    fn test_html() {
+        let input = r#"
+# Title
+
+<div class="flex p-4" title="not this">
+  Some prose, don't extract
+</div>
+
+a < b and {'mt-2'}
+"#;
+        assert_eq!(run(input, false), ["flex p-4"]);
+    }
+
+    #[test]
+    fn test_mdx() {
+        let input = r#"
+<div class="flex p-4">
+  Some prose, don't extract
+</div>
+
+<Card className={clsx('shadow', 'rounded')} title="Card" />
+<Card title="hello world" alt="photo of cat" className="p-4" />
+
+a < b and {'mt-2'}
+"#;
+        let mut res = MarkdownExtractor::mdx(input).collect::<Vec<_>>();
+        res.sort();
+        assert_eq!(res, ["flex p-4", "p-4", "rounded", "shadow"]);
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let input = r#"
+```html
+<div class="grid"></div>
+```
+
+~~~tsx
+const a = 'block'
+~~~
+
+<span class="inline"></span>
+"#;
+        assert_eq!(run(input, false), ["inline"]);
+        assert_eq!(run(input, true), ["block", "grid", "inline"]);
+    }
+
+    #[test]
+    fn test_front_matter() {
+        let input = r#"---
+title: Hello World
+class: "text-lg font-bold"
+tags: [news, 'rust']
+bodyClasses: [flex, 'grid']
+classes:
+  - underline
+authors:
+  - Jane
+---
+
+<p class="m-2">prose</p>
+"#;
+        assert_eq!(run(input, false), ["flex", "grid", "m-2", "text-lg font-bold", "underline"]);
+    }
+}
@@ -0,0 +1,342 @@
+/// Macros whose body is a view, `class` attributes inside them are extracted
+const VIEW_MACROS: &[&str] = &["view", "html", "rsx"];
+
+/// Extracts candidates from Rust web frameworks like Leptos, Yew and Dioxus
+///
+/// Only string literals, including raw strings, in `class` attributes of `view!`, `html!` and `rsx!`,
+/// e.g. `class="flex"`, `class: "flex"` or `class={...}`, and in `classes!(...)` are extracted.
+/// Leptos `class:name=...` directives yield `name`.
+pub struct RustExtractor<'a> {
+    input: &'a str,
+    items: Option<std::vec::IntoIter<&'a str>>,
+}
+
+impl<'a> RustExtractor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, items: None }
+    }
+
+    fn scan_all(&self) -> Vec<&'a str> {
+        let mut res = Vec::new();
+        self.scan(0, self.input.len(), false, &mut res);
+        res
+    }
+
+    /// Scan `input[start..end]`, `in_view` is whether it's inside a view macro
+    fn scan(&self, start: usize, end: usize, in_view: bool, res: &mut Vec<&'a str>) {
+        let bytes = self.input.as_bytes();
+        let mut i = start;
+        while i < end {
+            if let Some(next) = skip_trivia(bytes, i) {
+                i = next;
+                continue;
+            }
+            if let Some((_, next)) = string_at(bytes, i) {
+                i = next;
+                continue;
+            }
+            if !is_ident_start(bytes[i]) || (i > 0 && is_ident(bytes[i - 1])) {
+                i += 1;
+                continue;
+            }
+
+            let ident_end = i + bytes[i..end].iter().take_while(|c| is_ident(**c)).count();
+            let ident = &self.input[i..ident_end];
+            i = match ident {
+                "classes" => self.scan_macro(
+                    ident_end,
+                    end,
+                    |s, from, to, res| s.collect_strings(from, to, res),
+                    res,
+                ),
+                _ if VIEW_MACROS.contains(&ident) => self.scan_macro(
+                    ident_end,
+                    end,
+                    |s, from, to, res| s.scan(from, to, true, res),
+                    res,
+                ),
+                "class"
+                    if in_view && !matches!(bytes.get(i.wrapping_sub(1)), Some(b'-' | b'.')) =>
+                {
+                    self.scan_attribute(ident_end, end, res)
+                }
+                _ => ident_end,
+            };
+        }
+    }
+
+    /// `name!(...)`, `name! { ... }`, starting after `name`, calls `f` with the body
+    fn scan_macro(
+        &self,
+        start: usize,
+        end: usize,
+        f: impl FnOnce(&Self, usize, usize, &mut Vec<&'a str>),
+        res: &mut Vec<&'a str>,
+    ) -> usize {
+        let bytes = self.input.as_bytes();
+        if bytes.get(start) != Some(&b'!') {
+            return start;
+        }
+        let open = skip_whitespace(bytes, start + 1);
+        if !matches!(bytes.get(open), Some(b'(' | b'[' | b'{')) {
+            return start;
+        }
+        let close = skip_group(bytes, open).min(end);
+        let body_end = match bytes[close - 1] {
+            b')' | b']' | b'}' if close > open + 1 => close - 1,
+            _ => close,
+        };
+        f(self, open + 1, body_end, res);
+        close
+    }
+
+    /// The value of a `class` attribute, starting after `class`
+    fn scan_attribute(&self, start: usize, end: usize, res: &mut Vec<&'a str>) -> usize {
+        let bytes = self.input.as_bytes();
+        let mut i = skip_whitespace(bytes, start);
+        match bytes.get(i) {
+            // Leptos `class:bg-red-500=move || active()`
+            Some(b':')
+                if i == start
+                    && !matches!(
+                        bytes.get(i + 1),
+                        Some(b' ' | b'"' | b'\n' | b'\t' | b'\r' | b':')
+                    ) =>
+            {
+                let name_end = i
+                    + 1
+                    + bytes[i + 1..end]
+                        .iter()
+                        .take_while(|c| !c.is_ascii_whitespace() && **c != b'=')
+                        .count();
+                res.push(&self.input[i + 1..name_end]);
+                return name_end;
+            }
+            Some(b':') => (),
+            Some(b'=') if bytes.get(i + 1) != Some(&b'=') => (),
+            _ => return i,
+        }
+        i = skip_whitespace(bytes, i + 1);
+
+        if let Some((content, next)) = string_at(bytes, i) {
+            res.push(&self.input[content]);
+            return next;
+        }
+        if matches!(bytes.get(i), Some(b'(' | b'[' | b'{')) {
+            let close = skip_group(bytes, i).min(end);
+            self.collect_strings(i, close, res);
+            return close;
+        }
+
+        // an expression, e.g. Dioxus `class: if active { "a" } else { "b" },`
+        let mut j = i;
+        while j < end {
+            if let Some(next) = skip_trivia(bytes, j) {
+                j = next;
+                continue;
+            }
+            match bytes[j] {
+                b',' | b'>' | b')' | b']' | b'}' => break,
+                b'(' | b'[' | b'{' => j = skip_group(bytes, j),
+                _ => j = string_at(bytes, j).map_or(j + 1, |(_, next)| next),
+            }
+        }
+        let j = j.min(end);
+        self.collect_strings(i, j, res);
+        j
+    }
+
+    /// Push all string literals in `input[start..end]`
+    fn collect_strings(&self, start: usize, end: usize, res: &mut Vec<&'a str>) {
+        let bytes = self.input.as_bytes();
+        let mut i = start;
+        while i < end {
+            if let Some(next) = skip_trivia(bytes, i) {
+                i = next;
+            } else if let Some((content, next)) = string_at(bytes, i) {
+                res.push(&self.input[content]);
+                i = next;
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+fn is_ident_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_'
+}
+
+fn is_ident(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+fn skip_whitespace(bytes: &[u8], i: usize) -> usize {
+    i + bytes[i.min(bytes.len())..].iter().take_while(|c| c.is_ascii_whitespace()).count()
+}
+
+/// Skip a comment or a char literal at `i`, returns the position after it
+fn skip_trivia(bytes: &[u8], i: usize) -> Option<usize> {
+    match (bytes[i], bytes.get(i + 1)) {
+        (b'/', Some(b'/')) => {
+            Some(memchr::memchr(b'\n', &bytes[i..]).map_or(bytes.len(), |n| i + n + 1))
+        }
+        (b'/', Some(b'*')) => Some(
+            memchr::memmem::find(&bytes[i + 2..], b"*/").map_or(bytes.len(), |n| i + 2 + n + 2),
+        ),
+        // char literals like `'"'` and `'\''`, lifetimes are left as is
+        (b'\'', Some(b'\\')) => Some(
+            bytes
+                .get(i + 3..)
+                .and_then(|rest| memchr::memchr(b'\'', rest))
+                .map_or(bytes.len(), |n| i + 3 + n + 1),
+        ),
+        (b'\'', Some(_)) if bytes.get(i + 2) == Some(&b'\'') => Some(i + 3),
+        _ => None,
+    }
+}
+
+/// A string literal at `i`, e.g. `"flex"`, `r"flex"` or `r#"flex"#`,
+/// returns the range of its content and the position after it
+fn string_at(bytes: &[u8], i: usize) -> Option<(std::ops::Range<usize>, usize)> {
+    match bytes[i] {
+        // including byte and C strings like `b"flex"`
+        b'"' => {
+            let mut j = i + 1;
+            while j < bytes.len() && bytes[j] != b'"' {
+                if bytes[j] == b'\\' {
+                    j += 1;
+                }
+                j += 1;
+            }
+            let j = j.min(bytes.len());
+            Some((i + 1..j, (j + 1).min(bytes.len())))
+        }
+        b'r' if i == 0
+            || !is_ident(bytes[i - 1])
+            || (i == 1 || !is_ident(bytes[i - 2])) && matches!(bytes[i - 1], b'b' | b'c') =>
+        {
+            let hashes = bytes[i + 1..].iter().take_while(|c| **c == b'#').count();
+            let open = i + 1 + hashes;
+            if bytes.get(open) != Some(&b'"') {
+                return None;
+            }
+            let mut close = Vec::with_capacity(hashes + 1);
+            close.push(b'"');
+            close.resize(hashes + 1, b'#');
+            let end = memchr::memmem::find(&bytes[open + 1..], &close)
+                .map_or(bytes.len(), |n| open + 1 + n);
+            Some((open + 1..end, (end + close.len()).min(bytes.len())))
+        }
+        _ => None,
+    }
+}
+
+/// The position after the bracket group opening at `i`
+fn skip_group(bytes: &[u8], i: usize) -> usize {
+    let mut depth = 0usize;
+    let mut j = i;
+    while j < bytes.len() {
+        if let Some(next) = skip_trivia(bytes, j) {
+            j = next;
+            continue;
+        }
+        if let Some((_, next)) = string_at(bytes, j) {
+            j = next;
+            continue;
+        }
+        match bytes[j] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return j + 1;
+                }
+            }
+            _ => (),
+        }
+        j += 1;
+    }
+    bytes.len()
+}
+
+impl<'a> Iterator for RustExtractor<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.items.is_none() {
+            self.items = Some(self.scan_all().into_iter());
+        }
+        self.items.as_mut()?.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::UniqueCandidate;
+
+    fn run(input: &str) -> Vec<Cow<'_, str>> {
+        let mut res = RustExtractor::new(input).filter_invalid().into_iter().collect::<Vec<_>>();
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn test_leptos() {
+        let input = r##"
+#[component]
+fn Counter() -> impl IntoView {
+    let label = "not-extracted";
+    view! {
+        <button class="px-4 py-2" class:bg-red-500=move || count() > 3 on:click=inc>
+            "Click me"
+        </button>
+        <p class=r#"text-sm "quoted""#>{move || view! { <span class="font-bold"/> }}</p>
+        <div class=("grid", move || wide())></div>
+    }
+}
+"##;
+        assert_eq!(run(input), ["bg-red-500", "font-bold", "grid", "px-4", "py-2", "text-sm"]);
+    }
+
+    #[test]
+    fn test_yew() {
+        let input = r#"
+let extra = classes!("shadow", props.class.clone());
+html! {
+    <div class={classes!("flex", active.then_some("ring-2"))} id="app">
+        { "Hello" }
+    </div>
+}
+"#;
+        assert_eq!(run(input), ["flex", "ring-2", "shadow"]);
+    }
+
+    #[test]
+    fn test_dioxus() {
+        let input = r#"
+rsx! {
+    div {
+        class: "mx-auto",
+        // class: "commented",
+        h1 { class: if big { "text-4xl" } else { "text-xl" }, "Title" }
+        p { "class: text" }
+    }
+}
+"#;
+        assert_eq!(run(input), ["mx-auto", "text-4xl", "text-xl"]);
+    }
+
+    #[test]
+    fn test_outside_macros() {
+        let input = r#"
+fn class() -> &'static str { "block" }
+let c = '"';
+let s = r"inline";
+"#;
+        assert!(run(input).is_empty());
+    }
+}
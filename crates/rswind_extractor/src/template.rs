@@ -0,0 +1,343 @@
+use memchr::memmem;
+
+use crate::ecma::EcmaExtractor;
+
+/// A server-side template language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// Laravel Blade, `{{ }}`, `{!! !!}`, `@class([...])` and `$attributes->class([...])`
+    Blade,
+    /// Twig, `{{ }}`, `{% %}` and `html_classes(...)`
+    Twig,
+    /// Jinja and Nunjucks, `{{ }}` and `{% %}`
+    Jinja,
+    /// Embedded Ruby, `<% %>`, `<%= %>` and `class_names(...)`
+    Erb,
+    /// Phoenix HEEx, `class={[...]}` and `<%= %>`
+    Heex,
+    /// Elixir modules, only `~H` sigils are scanned as HEEx
+    Elixir,
+    /// ASP.NET Razor, `@(...)` and `@{ }`
+    Razor,
+}
+
+impl Dialect {
+    /// Interpolation delimiters inside a `class` attribute value
+    fn delimiters(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Dialect::Blade => &[("{{", "}}"), ("{!!", "!!}")],
+            Dialect::Twig | Dialect::Jinja => &[("{{", "}}"), ("{%", "%}")],
+            Dialect::Erb => &[("<%", "%>")],
+            Dialect::Heex | Dialect::Elixir => &[("<%", "%>"), ("{", "}")],
+            Dialect::Razor => &[("@(", ")"), ("@{", "}")],
+        }
+    }
+
+    /// Functions and directives building a class list from their arguments
+    fn class_functions(self) -> &'static [&'static str] {
+        match self {
+            Dialect::Blade => &["@class", "->class"],
+            Dialect::Twig => &["html_classes"],
+            Dialect::Erb => &["class_names", "token_list"],
+            Dialect::Jinja | Dialect::Heex | Dialect::Elixir | Dialect::Razor => &[],
+        }
+    }
+}
+
+/// Extracts candidates from server-side templates
+///
+/// Scans `class` attributes, where static text is extracted as is
+/// and strings inside interpolations like `{{ $active ? 'font-bold' : '' }}` are extracted,
+/// and the arguments of conditional class directives like `@class(['p-4', 'font-bold' => $active])`.
+/// Everything else in the template is ignored.
+pub struct TemplateExtractor<'a> {
+    input: &'a str,
+    dialect: Dialect,
+    items: Option<std::vec::IntoIter<&'a str>>,
+}
+
+#[derive(Clone, Copy)]
+enum Hit {
+    Attribute,
+    Function(usize),
+}
+
+impl<'a> TemplateExtractor<'a> {
+    pub fn new(input: &'a str, dialect: Dialect) -> Self {
+        Self { input, dialect, items: None }
+    }
+
+    fn scan_all(&self) -> Vec<&'a str> {
+        if self.dialect == Dialect::Elixir {
+            return heex_sigils(self.input)
+                .flat_map(|sigil| TemplateExtractor::new(sigil, Dialect::Heex).scan_all())
+                .collect();
+        }
+
+        let bytes = self.input.as_bytes();
+        let mut hits =
+            memmem::find_iter(bytes, "class").map(|i| (i, Hit::Attribute)).collect::<Vec<_>>();
+        for function in self.dialect.class_functions() {
+            hits.extend(
+                memmem::find_iter(bytes, function).map(|i| (i, Hit::Function(function.len()))),
+            );
+        }
+        hits.sort_by_key(|(i, _)| *i);
+
+        let mut res = Vec::new();
+        let mut consumed = 0;
+        for (pos, hit) in hits {
+            if pos < consumed {
+                continue;
+            }
+            consumed = match hit {
+                Hit::Attribute => self.scan_attribute(pos, &mut res),
+                Hit::Function(len) => self.scan_function(pos + len, &mut res),
+            };
+        }
+        res
+    }
+
+    /// `html_classes('flex', { 'font-bold': active })`, starting after the function name
+    fn scan_function(&self, start: usize, res: &mut Vec<&'a str>) -> usize {
+        let rest = &self.input[start..];
+        let Some(args) = rest.strip_prefix('(') else { return start };
+        let end = find_close(args, ")").unwrap_or(args.len());
+        res.extend(EcmaExtractor::new(&args[..end]));
+        start + 1 + end
+    }
+
+    /// `class="..."`, `className="..."`, `:class="..."` or `class={...}`, starting at `class`
+    fn scan_attribute(&self, pos: usize, res: &mut Vec<&'a str>) -> usize {
+        let bytes = self.input.as_bytes();
+        let name_start = bytes[..pos]
+            .iter()
+            .rposition(|c| !(c.is_ascii_alphanumeric() || matches!(c, b':' | b'-' | b'_' | b'.')))
+            .map_or(0, |i| i + 1);
+        if name_start > 0 && !bytes[name_start - 1].is_ascii_whitespace() {
+            return pos;
+        }
+
+        let rest = &self.input[pos..];
+        let name_end = pos + rest.strip_prefix("className").map_or(5, |_| 9);
+        let prefix = &self.input[name_start..pos];
+        let Some(value) = self.input[name_end..].strip_prefix('=') else { return pos };
+        let value_start = name_end + 1;
+
+        match (prefix, value.as_bytes().first()) {
+            // `class="..."`
+            ("", Some(&quote @ (b'"' | b'\''))) => {
+                self.scan_class_value(value_start + 1, quote, res)
+            }
+            // `class={[...]}`
+            ("", Some(b'{')) => {
+                let end = find_close(&value[1..], "}").unwrap_or(value.len() - 1);
+                res.extend(EcmaExtractor::new(&value[1..1 + end]));
+                value_start + 1 + end
+            }
+            // bound classes, e.g. `:class="{ 'font-bold': active }"` or `x-bind:class="..."`
+            (prefix, Some(&quote @ (b'"' | b'\''))) if prefix.ends_with(':') => {
+                let end = memchr::memchr(quote, &value.as_bytes()[1..]).unwrap_or(value.len() - 1);
+                res.extend(EcmaExtractor::new(&value[1..1 + end]));
+                value_start + 1 + end
+            }
+            _ => pos,
+        }
+    }
+
+    /// Scan a quoted value, static chunks are extracted and interpolations are scanned as code
+    fn scan_class_value(&self, start: usize, quote: u8, res: &mut Vec<&'a str>) -> usize {
+        let bytes = self.input.as_bytes();
+        let mut chunk = start;
+        let mut i = start;
+        while i < bytes.len() {
+            if bytes[i] == quote {
+                res.push(&self.input[chunk..i]);
+                return i + 1;
+            }
+
+            let rest = &self.input[i..];
+            let delimiter = match bytes[i] {
+                b'{' | b'<' | b'@' => {
+                    self.dialect.delimiters().iter().find(|(open, _)| rest.starts_with(open))
+                }
+                _ => None,
+            };
+            if let Some((open, close)) = delimiter {
+                res.push(&self.input[chunk..i]);
+                let code = &rest[open.len()..];
+                let end = find_close(code, close).unwrap_or(code.len());
+                res.extend(EcmaExtractor::new(&code[..end]));
+                i += open.len() + (end + close.len()).min(code.len());
+                chunk = i;
+                continue;
+            }
+            i += 1;
+        }
+
+        res.push(&self.input[chunk..]);
+        bytes.len()
+    }
+}
+
+/// Contents of `~H` sigils, e.g. `~H"""<div class="flex"></div>"""`
+fn heex_sigils(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || loop {
+        let start = memmem::find(rest.as_bytes(), b"~H")?;
+        rest = &rest[start + 2..];
+        let close = match rest.as_bytes().first() {
+            _ if rest.starts_with(r#"""""#) => r#"""""#,
+            Some(b'"') => "\"",
+            Some(b'\'') => "'",
+            Some(b'(') => ")",
+            Some(b'[') => "]",
+            Some(b'{') => "}",
+            Some(b'<') => ">",
+            Some(b'/') => "/",
+            Some(b'|') => "|",
+            _ => continue,
+        };
+        let open = if close.len() == 3 { 3 } else { 1 };
+        let content = &rest[open..];
+        let end = memmem::find(content.as_bytes(), close.as_bytes()).unwrap_or(content.len());
+        rest = &content[end..];
+        return Some(&content[..end]);
+    })
+}
+
+/// Find `close` in `code`, skipping quoted strings and nested brackets of the same kind
+fn find_close(code: &str, close: &str) -> Option<usize> {
+    let bytes = code.as_bytes();
+    let first = close.as_bytes()[0];
+    let open = match close {
+        "}" => Some(b'{'),
+        ")" => Some(b'('),
+        _ => None,
+    };
+
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            c if Some(c) == open => depth += 1,
+            c if c == first && depth > 0 => depth -= 1,
+            c if c == first && code[i..].starts_with(close) => return Some(i),
+            _ => (),
+        }
+        i += 1;
+    }
+    None
+}
+
+impl<'a> Iterator for TemplateExtractor<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.items.is_none() {
+            self.items = Some(self.scan_all().into_iter());
+        }
+        self.items.as_mut()?.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::UniqueCandidate;
+
+    fn run(input: &str, dialect: Dialect) -> Vec<Cow<'_, str>> {
+        let mut res =
+            TemplateExtractor::new(input, dialect).filter_invalid().into_iter().collect::<Vec<_>>();
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn test_blade() {
+        let input = r#"
+<div class="flex {{ $active ? 'font-bold' : "font-normal" }} p-4">{{ $title }}</div>
+<span @class(['mt-2', 'text-red-500' => $hasError, "hidden" => !$show])></span>
+<x-button :class="$primary ? 'bg-blue-500' : 'bg-gray-500'" data-class="ignored" />
+<p>class="ignored" in text</p>
+"#;
+        assert_eq!(
+            run(input, Dialect::Blade),
+            [
+                "bg-blue-500",
+                "bg-gray-500",
+                "flex",
+                "font-bold",
+                "font-normal",
+                "hidden",
+                "mt-2",
+                "p-4",
+                "text-red-500"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_twig_and_jinja() {
+        let input = r#"
+<div class="card {% if active %}ring-2{% endif %} {{ 'shadow' if raised else 'border' }}"></div>
+<div class="{{ html_classes('grid', { 'gap-4': wide }) }}"></div>
+"#;
+        let expected = ["border", "card", "gap-4", "grid", "ring-2", "shadow"];
+        assert_eq!(run(input, Dialect::Twig), expected);
+        assert_eq!(run(input, Dialect::Jinja), expected);
+    }
+
+    #[test]
+    fn test_erb() {
+        let input = r#"
+<div class="block <%= "underline" if link? %>"></div>
+<%= tag.div class: class_names("italic", "uppercase": loud) %>
+"#;
+        assert_eq!(run(input, Dialect::Erb), ["block", "italic", "underline", "uppercase"]);
+    }
+
+    #[test]
+    fn test_heex() {
+        let input = r#"
+<div class={["px-4", @active && "bg-zinc-100", if(@round, do: "rounded")]}>
+  <span class="text-sm"><%= @label %></span>
+</div>
+"#;
+        assert_eq!(run(input, Dialect::Heex), ["bg-zinc-100", "px-4", "rounded", "text-sm"]);
+    }
+
+    #[test]
+    fn test_elixir_sigils() {
+        let input = r#"
+@doc "Renders a <div class=\"ignored\">"
+def badge(assigns), do: ~H(<span class="badge"><%= @label %></span>)
+
+def card(assigns) do
+  ~H"""
+  <div class={["card", @class]}>{render_slot(@inner_block)}</div>
+  """
+end
+"#;
+        assert_eq!(run(input, Dialect::Elixir), ["badge", "card"]);
+    }
+
+    #[test]
+    fn test_razor() {
+        let input = r#"
+<li class="item @(Model.Active ? "bg-white" : "bg-black") @{ var x = "m-1"; }"></li>
+"#;
+        assert_eq!(run(input, Dialect::Razor), ["bg-black", "bg-white", "item", "m-1"]);
+    }
+}
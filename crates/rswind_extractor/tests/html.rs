@@ -1,9 +1,15 @@
-use rswind_extractor::html::HtmlExtractor;
+use std::borrow::Cow;
 
-fn run(input: &str) -> Vec<&str> {
+use rswind_extractor::html::{FileType, HtmlExtractor};
+
+fn run(input: &str) -> Vec<Cow<'_, str>> {
     HtmlExtractor::new(input).collect()
 }
 
+fn run_with(input: &str, file_type: FileType) -> Vec<Cow<'_, str>> {
+    HtmlExtractor::new(input).apply_options(|o| o.file_type = file_type).collect()
+}
+
 #[test]
 fn test_normal_tag() {
     let input = r#"<a></a>"#;
@@ -178,3 +184,59 @@ fn test_error_recovery_6() {
     let actual = run(input);
     assert_eq!(actual, ["f2 f3", "f3"]);
 }
+
+// --- framework bindings ---
+
+#[test]
+fn test_vue_class_object() {
+    let input = r#"<div :class="{ active: isActive, 'text-red-500': hasError }"></div>"#;
+    assert_eq!(run_with(input, FileType::Vue), ["text-red-500", "active"]);
+}
+
+#[test]
+fn test_vue_class_array() {
+    let input = r#"<div :class="[isActive ? 'ring-2' : '', { shadow }]"></div>"#;
+    assert_eq!(run_with(input, FileType::Vue), ["ring-2", "shadow"]);
+}
+
+#[test]
+fn test_vue_v_bind_class() {
+    let input = r#"<div v-bind:class="{ hidden: !show }" class="flex"></div>"#;
+    assert_eq!(run_with(input, FileType::Vue), ["hidden", "flex"]);
+}
+
+#[test]
+fn test_vue_other_bindings_are_not_keys() {
+    let input = r#"<comp :style="{ color: c }" :items="['a']"></comp>"#;
+    assert_eq!(run_with(input, FileType::Vue), ["a"]);
+}
+
+#[test]
+fn test_svelte_class_directive() {
+    let input = r#"<div class:bg-red-500={active} class:hidden></div>"#;
+    assert_eq!(run_with(input, FileType::Svelte), ["bg-red-500", "hidden"]);
+}
+
+#[test]
+fn test_svelte_class_expression() {
+    let input = r#"<div class={big ? 'text-xl' : 'text-sm'} title="tip"></div>"#;
+    assert_eq!(run_with(input, FileType::Svelte), ["text-xl", "text-sm", "tip"]);
+}
+
+#[test]
+fn test_svelte_class_object() {
+    let input = r#"<div class={{ underline: link, 'font-bold': strong }}></div>"#;
+    assert_eq!(run_with(input, FileType::Svelte), ["font-bold", "underline"]);
+}
+
+#[test]
+fn test_astro_class_list() {
+    let input = r#"<div class:list={['box', { red: isRed }, [nested && 'mt-2']]}></div>"#;
+    assert_eq!(run_with(input, FileType::Astro), ["box", "mt-2", "red"]);
+}
+
+#[test]
+fn test_html_braces_are_plain() {
+    let input = r#"<div class={flex}></div>"#;
+    assert_eq!(run_with(input, FileType::Html), ["flex"]);
+}
@@ -0,0 +1,68 @@
+//! Attribute syntaxes from the HTML spec, as produced by CMS exports and legacy templates
+
+use std::borrow::Cow;
+
+use rswind_extractor::html::HtmlExtractor;
+
+fn run(input: &str) -> Vec<Cow<'_, str>> {
+    HtmlExtractor::new(input).collect()
+}
+
+const CASES: &[(&str, &[&str])] = &[
+    // quoting
+    (r#"<div class="flex p-4"></div>"#, &["flex p-4"]),
+    (r#"<div class='flex p-4'></div>"#, &["flex p-4"]),
+    (r#"<div class=flex></div>"#, &["flex"]),
+    (r#"<div class=flex id=main></div>"#, &["flex", "main"]),
+    (r#"<img class=block/>"#, &["block/"]),
+    (r#"<div class='say "hi"'></div>"#, &["say \"hi\""]),
+    (r#"<div class="it's"></div>"#, &["it's"]),
+    (r#"<div class=''></div><p class="m-2"></p>"#, &["", "m-2"]),
+    // attributes without values
+    (r#"<input disabled class="border">"#, &["border"]),
+    // whitespace around `=`
+    (r#"<div class= "flex"></div>"#, &["flex"]),
+    (r#"<div class = 'p-4' id =main></div>"#, &["p-4", "main"]),
+    (
+        r#"<div class=
+        "grid"></div>"#,
+        &["grid"],
+    ),
+    // character references
+    (r#"<div class="content-[&quot;hi&quot;]"></div>"#, &["content-[\"hi\"]"]),
+    (r#"<div class="[&amp;>*]:p-4"></div>"#, &["[&>*]:p-4"]),
+    (r#"<div class="[&#39;a&#x27;]"></div>"#, &["['a']"]),
+    (r#"<div class="a&b &unknown; &lt"></div>"#, &["a&b &unknown; &lt"]),
+    (r#"<div class=&quot;x&quot;></div>"#, &["\"x\""]),
+    (r#"<div class="a&nbsp;b"></div>"#, &["a\u{a0}b"]),
+    // comments
+    (r#"<!-- <div class="hidden"> --><div class="block"></div>"#, &["block"]),
+    (r#"<!----><!-- a -- b ---><i class="italic"></i>"#, &["italic"]),
+    (r#"<!DOCTYPE html><html class="dark"></html>"#, &["dark"]),
+    // template contents are markup
+    (
+        r#"<template id="row"><tr class="odd:bg-white"><td class="p-2"></td></tr></template>"#,
+        &["row", "odd:bg-white", "p-2"],
+    ),
+    // svg subtrees only extract `class`
+    (
+        r#"<svg class="h-4 w-4" viewBox="0 0 24 24"><path class="fill-current" d="m12 4l8 8"/></svg><a href="x" class="link"></a>"#,
+        &["h-4 w-4", "fill-current", "x", "link"],
+    ),
+    (
+        r#"<svg><g><svg class="inner"><rect x="1"/></svg><circle r="2" class="c"/></g></svg><p title="t"></p>"#,
+        &["inner", "c", "t"],
+    ),
+    (
+        r#"<svg class="w-4"/><div id="main" data-x="foo" class="flex"></div>"#,
+        &["w-4", "main", "foo", "flex"],
+    ),
+    (r#"<svg class="w-4" /><p title="t"></p>"#, &["w-4", "t"]),
+];
+
+#[test]
+fn test_conformance() {
+    for (input, expected) in CASES {
+        assert_eq!(run(input), *expected, "input: {input}");
+    }
+}
@@ -0,0 +1,51 @@
+use std::borrow::Cow;
+
+use rswind_extractor::{ExtractOptions, Extractable, Extractor};
+
+fn run<'a>(input: &'a str, kind: &str, options: &'a ExtractOptions) -> Vec<Cow<'a, str>> {
+    let mut res =
+        Extractor::new(input, kind).with_options(options).extract().into_iter().collect::<Vec<_>>();
+    res.sort();
+    res
+}
+
+#[test]
+fn test_mdx() {
+    let input = r#"---
+title: Getting started
+---
+
+import { Callout } from './callout'
+
+Tailwind-like utilities, e.g. `flex` or `grid`, are not extracted from prose.
+
+<Callout className="mt-4 text-sm">
+  It's a *callout*
+</Callout>
+
+```tsx
+const a = 'block'
+```
+"#;
+
+    assert_eq!(run(input, "mdx", &ExtractOptions::default()), ["mt-4", "text-sm"]);
+
+    let options = ExtractOptions { code_blocks: true, ..Default::default() };
+    assert_eq!(run(input, "mdx", &options), ["block", "mt-4", "text-sm"]);
+}
+
+#[test]
+fn test_md() {
+    let input = r#"# Notes
+
+Write {'italic'} or <kbd>Ctrl</kbd>, `flex` and 'grid' are only prose.
+
+<div class="grid gap-4">
+  <span className="underline">A note</span>
+</div>
+"#;
+
+    let options = ExtractOptions::default();
+    assert_eq!(run(input, "md", &options), ["gap-4", "grid", "underline"]);
+    assert_eq!(run(input, "mdx", &options), ["gap-4", "grid", "underline"]);
+}
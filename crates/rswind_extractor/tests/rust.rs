@@ -0,0 +1,28 @@
+use rswind_extractor::{Extractable, Extractor};
+
+#[test]
+fn test_leptos_component() {
+    let input = r##"
+use leptos::*;
+
+/// A button, `class="doc-comment"` is not extracted
+#[component]
+pub fn Button(#[prop(into)] label: String) -> impl IntoView {
+    let (active, set_active) = create_signal(false);
+    view! {
+        <button
+            class="rounded-md px-3"
+            class=("ring-2", move || active())
+            class:opacity-50=move || !active()
+            on:click=move |_| set_active.update(|a| *a = !*a)
+        >
+            {label}
+        </button>
+    }
+}
+"##;
+
+    let mut res = Extractor::new(input, "rs").extract().into_iter().collect::<Vec<_>>();
+    res.sort();
+    assert_eq!(res, ["opacity-50", "px-3", "ring-2", "rounded-md"]);
+}
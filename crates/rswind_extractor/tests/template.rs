@@ -0,0 +1,51 @@
+use std::borrow::Cow;
+
+use rswind_extractor::{Extractable, Extractor};
+
+fn run<'a>(input: &'a str, kind: &str) -> Vec<Cow<'a, str>> {
+    let mut res = Extractor::new(input, kind).extract().into_iter().collect::<Vec<_>>();
+    res.sort();
+    res
+}
+
+#[test]
+fn test_blade_component() {
+    let input = r#"
+@props(['active' => false])
+
+<a {{ $attributes->class(['underline']) }}
+   @class(['px-3 py-2', 'bg-gray-900 text-white' => $active, 'text-gray-300' => ! $active])>
+    {{ $slot }}
+</a>
+<div class="rounded {{ $active ? 'ring-2' : '' }}">@lang('menu.title')</div>
+"#;
+
+    assert_eq!(
+        run(input, "blade.php"),
+        [
+            "bg-gray-900",
+            "px-3",
+            "py-2",
+            "ring-2",
+            "rounded",
+            "text-gray-300",
+            "text-white",
+            "underline"
+        ]
+    );
+}
+
+#[test]
+fn test_heex_component() {
+    let input = r#"
+def button(assigns) do
+  ~H"""
+  <button class={["phx-submit-loading:opacity-75 rounded-lg", @class]} {@rest}>
+    <%= render_slot(@inner_block) %>
+  </button>
+  """
+end
+"#;
+
+    assert_eq!(run(input, "ex"), ["phx-submit-loading:opacity-75", "rounded-lg"]);
+}
@@ -127,9 +127,11 @@ impl Extend<(SmolStr, SmolStr)> for ThemeMap {
         match self {
             Self::Dynamic(map) => map.extend(iter),
             Self::Static(s) => {
+                // values from `iter` take precedence over the static ones
                 *self = Self::Dynamic(
-                    iter.into_iter()
-                        .chain(s.into_iter().map(|(k, v)| (SmolStr::from(*k), SmolStr::from(*v))))
+                    s.into_iter()
+                        .map(|(k, v)| (SmolStr::from(*k), SmolStr::from(*v)))
+                        .chain(iter)
                         .collect(),
                 )
             }
@@ -154,6 +156,28 @@ impl Theme {
         self.get(key).and_then(|v| v.get(inner_key))
     }
 
+    /// Resolve a dotted path like `colors.blue.500`, `spacing[2.5]` or `fontSize.lg` to a CSS value,
+    /// a path without inner key resolves to `DEFAULT`
+    pub fn resolve(&self, path: &str) -> Option<SmolStr> {
+        let path = path.replace('[', ".").replace(']', "");
+        let (key, inner_key) = path.split_once('.').unwrap_or((&path, "DEFAULT"));
+        let map = self.get(key)?;
+
+        match map.get_value(&inner_key.replace('.', "-")).or_else(|| map.get_value(inner_key))? {
+            ThemeValue::Plain(value) => Some(value),
+            ThemeValue::FontSize(
+                FontSize::Plain(size)
+                | FontSize::WithLineHeight((size, _))
+                | FontSize::WithConfig((size, _)),
+            ) => Some(size.clone()),
+            ThemeValue::FontFamily(
+                FontFamily::Plain(family) | FontFamily::WithConfig((family, _)),
+            ) => Some(family.clone()),
+            ThemeValue::FontFamily(FontFamily::Multi(families)) => Some(families.join(", ").into()),
+            ThemeValue::KeyFrames(_) => None,
+        }
+    }
+
     pub fn merge(&mut self, user_theme: &mut ThemeConfig) {
         for (key, value) in mem::take(&mut user_theme.replace).into_iter() {
             self.insert(key, Arc::new(value));
@@ -194,3 +218,19 @@ impl From<HashMap<SmolStr, Arc<ThemeMap>>> for Theme {
         Theme(map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static COLORS: Map<&str, &str> = phf_map! { "red" => "#f00", "blue" => "#00f" };
+
+    #[test]
+    fn test_extend_static() {
+        let mut map = ThemeMap::Static(&COLORS);
+        map.extend([(SmolStr::from("red"), SmolStr::from("#e00"))]);
+
+        assert_eq!(map.get_ref("red"), Some("#e00"));
+        assert_eq!(map.get_ref("blue"), Some("#00f"));
+    }
+}
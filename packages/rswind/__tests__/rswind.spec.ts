@@ -80,4 +80,115 @@ describe('rswind', () => {
       "
     `)
   })
+
+  it('should work with js plugins', () => {
+    const app = createGenerator({
+      config: false,
+      plugins: [
+        {
+          name: 'tab',
+          addUtilities: {
+            '.content-auto': { contentVisibility: 'auto' },
+          },
+          matchUtilities: [
+            {
+              key: 'tab',
+              theme: 'spacing',
+              type: 'number',
+              handler: value => ({ tabSize: value }),
+            },
+          ],
+          addVariant: { optional: '&:optional' },
+        },
+      ],
+    })
+
+    const res = app.generateCandidate(['content-auto', 'tab-4', 'optional:tab-[3]'])
+
+    expect(res.css).toMatchInlineSnapshot(`
+      ".content-auto {
+        content-visibility: auto;
+      }
+      .tab-4 {
+        tab-size: 1rem;
+      }
+      .optional\\:tab-\\[3\\]:optional {
+        tab-size: 3;
+      }
+      "
+    `)
+  })
+
+  it('should skip values js handlers return null for', () => {
+    const app = createGenerator({
+      config: false,
+      plugins: [
+        {
+          name: 'tab',
+          matchUtilities: [
+            { key: 'tab', type: 'number', handler: value => (value === '2' ? null : { tabSize: value }) },
+          ],
+        },
+      ],
+    })
+
+    expect(app.generateCandidate(['tab-[2]']).css).toBe('')
+  })
+
+  it('should throw errors of js handlers', () => {
+    const app = createGenerator({
+      config: false,
+      plugins: [
+        {
+          name: 'tab',
+          matchUtilities: [
+            {
+              key: 'tab',
+              type: 'number',
+              handler: () => {
+                throw new Error('oops')
+              },
+            },
+          ],
+        },
+      ],
+    })
+
+    expect(() => app.generateCandidate(['tab-[2]'])).toThrow('oops')
+  })
+
+  it('should work with js extractors', () => {
+    const app = createGenerator({
+      config: { content: ['./**/*.tpl'], extract: { transforms: [{ stripPrefix: 'x-' }] } },
+      extractors: [{ pattern: 'tpl', extract: content => content.split(',') }],
+    })
+
+    const res = app.generateWith([[`${process.cwd()}/index.tpl`, 'flex,x-p-4']])
+
+    expect(res.css).toMatchInlineSnapshot(`
+      ".p-4 {
+        padding: 1rem;
+      }
+      .flex {
+        display: flex;
+      }
+      "
+    `)
+  })
+
+  it('should throw errors of js extractors', () => {
+    const app = createGenerator({
+      config: false,
+      extractors: [
+        {
+          pattern: 'tpl',
+          extract: () => {
+            throw new Error('oops')
+          },
+        },
+      ],
+    })
+
+    expect(() => app.generateWith([[`${process.cwd()}/index.tpl`, 'flex']])).toThrow('oops')
+  })
 })
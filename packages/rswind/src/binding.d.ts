@@ -1,4 +1,4 @@
-import type { GeneratorConfig } from './types'
+import type { GeneratorConfig, ValueDefinition } from './types'
 
 export class Generator {
   generateWith(candidates: Array<[string, string]>): GenerateResult
@@ -18,7 +18,52 @@ export interface GeneratorOptions {
   base?: string
   config?: string | false | GeneratorConfig
   watch?: boolean
+  /** Extract and generate in parallel, calls to JavaScript handlers still run one at a time */
   parallel?: boolean
+  plugins?: Array<JsPlugin>
+  /** Custom extractors, the first matching one is used for a file */
+  extractors?: Array<JsExtractor>
+}
+
+/** A custom extractor defined in JavaScript */
+export interface JsExtractor {
+  /** An extension like `tpl`, or a glob relative to the base directory like `views/**\/*.html` */
+  pattern: string
+  /** Called with the file content, returns the candidates. Errors are thrown after the generation */
+  extract: (content: string) => string[]
+}
+
+/** A css object, e.g. `{ color: 'red', '&:hover': { color: 'blue' } }` */
+export interface CssObject {
+  [key: string]: string | number | CssObject
+}
+
+/** A plugin defined in JavaScript */
+export interface JsPlugin {
+  name: string
+  /** Static utilities, e.g. `{ 'content-auto': { contentVisibility: 'auto' } }` */
+  addUtilities?: Record<string, CssObject>
+  /** Dynamic utilities with a JavaScript handler */
+  matchUtilities?: Array<MatchUtility>
+  /** Static variants, e.g. `{ optional: '&:optional' }` */
+  addVariant?: Record<string, string | string[]>
+}
+
+export interface MatchUtility {
+  /** The key of the utility, e.g. `tab` */
+  key: string
+  /**
+   * Called with the resolved value and modifier, returns `null` if the value doesn't match.
+   * Results are cached, errors are thrown after the generation
+   */
+  handler: (value: string, modifier: string | null) => CssObject | null | undefined
+  /** The theme key to read values from, e.g. `spacing` */
+  theme?: string
+  /** The type validator for arbitrary values, e.g. `length` */
+  type?: string
+  modifier?: ValueDefinition
+  negative?: boolean
+  fraction?: boolean
 }
 
 export enum ResultKind {
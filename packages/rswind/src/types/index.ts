@@ -6,11 +6,22 @@ export type * from './property'
 
 export interface GeneratorConfig {
   /**
-   * The glob pattern to match input files
+   * The glob pattern to match input files, patterns starting with `!` are excluded
    *
-   * @default ['./**\/*.{html,js,jsx,mjs,cjs,ts,tsx,mts,cts,vue,svelte,mdx}']
+   * @default ['./**\/*.{html,js,jsx,mjs,cjs,ts,tsx,mts,cts,vue,svelte,astro,mdx}']
    */
   content?: string[]
+  /**
+   * Globs of files and directories to skip, relative to the base directory,
+   * globs starting with `**` apply below every walked directory.
+   * Files ignored by `.gitignore` or `.ignore` are always skipped.
+   */
+  exclude?: string[]
+  /**
+   * Input stylesheets processed by the CLI, generated CSS is inserted at their
+   * `@tailwind` or `@import "rswind"` markers
+   */
+  stylesheets?: { input: string, output: string }[]
   /**
    * How to handle `dark:` variant, can be `media` or `selector`
    */
@@ -27,9 +38,44 @@ export interface GeneratorConfig {
    * User defined dynamic utilities, e.g. `bg-blue-500`
    */
   utilities?: UtilityBuilder[]
+  /**
+   * How candidates are extracted from source files
+   */
+  extract?: ExtractConfig
   [k: string]: unknown
 }
 
+export interface ExtractConfig {
+  /**
+   * Extract only from class functions and class attributes in JavaScript and TypeScript files,
+   * instead of every string literal, e.g. `{}` to use the default names
+   */
+  classFunctions?: {
+    /**
+     * Functions and template tags
+     *
+     * @default ['clsx', 'cn', 'cva', 'tw']
+     */
+    functions?: string[]
+    /**
+     * JSX attributes and object properties
+     *
+     * @default ['className', 'class']
+     */
+    attributes?: string[]
+  }
+  /**
+   * Extract candidates from fenced code blocks in markdown and MDX files
+   *
+   * @default false
+   */
+  codeBlocks?: boolean
+  /**
+   * Transforms applied to each candidate in order, e.g. `[{ stripPrefix: 'x-' }]`
+   */
+  transforms?: Array<{ stripPrefix: string } | { stripSuffix: string }>
+}
+
 export interface Features {
   /**
    * Use a lexer to parse candidate, default to `true` if set to `false`, the parser will use regex to parse candidate
@@ -61,8 +107,11 @@ export interface UtilityBuilder {
   additionalCss?: Rule | null
   /**
    * The css handler for the utility, e.g. `background-color: $1`
+   *
+   * `$0` / `${0}` for the value, `$1` / `${1}` for the modifier, `${raw}` for the value as written,
+   * `${1:-fallback}` for a fallback, `${1:+text}` for a conditional text, objects for nested rules
    */
-  css?: Record<string, string> | null
+  css?: CssTemplate | null
   /**
    * The key of the utility， e.g. `bg`
    */
@@ -93,6 +142,10 @@ export interface UtilityBuilder {
   selector?: string | null
 }
 
+export interface CssTemplate {
+  [key: string]: string | CssTemplate
+}
+
 export interface ValueDefinition {
   type?: Property | DataType | null
   theme?: ThemeKey | null
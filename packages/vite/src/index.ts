@@ -48,6 +48,16 @@ export default function rswind(options?: GeneratorOptions): Plugin[] {
         server.ws.on(WS_EVENT_PREFIX, (length) => {
           modulesQueue.flush(length)
         })
+
+        // restart the server to create the generator again with the new config,
+        // if the new config is invalid, vite keeps the running server
+        const configFiles = generator.configFiles()
+        server.watcher.add(configFiles)
+        server.watcher.on('change', (file) => {
+          if (configFiles.includes(file)) {
+            server?.restart()
+          }
+        })
       },
       buildStart() {
         // warm up the generator